@@ -0,0 +1,124 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use super::KString;
+use super::ValueView;
+
+/// Locale-aware ordering of strings.
+///
+/// Without a collation, strings compare by code point, putting `Äpfel` after `Zebra`.  With the
+/// `collation` feature, a locale like `de` or `sv` orders them the way readers of that language
+/// expect.
+#[derive(Debug, Clone)]
+pub struct Collation {
+    locale: KString,
+    #[cfg(feature = "collation")]
+    collators: std::sync::Arc<Collators>,
+}
+
+#[cfg(feature = "collation")]
+#[derive(Debug)]
+struct Collators {
+    strict: icu_collator::Collator,
+    ignore_case: icu_collator::Collator,
+}
+
+impl Collation {
+    /// Look up the collation for a locale, like `de` or `en-US`.
+    ///
+    /// Returns `None` for malformed locales or without the `collation` feature.
+    #[cfg(feature = "collation")]
+    pub fn new(locale: &str) -> Option<Self> {
+        use icu_collator::{Collator, CollatorOptions, Strength};
+
+        let data_locale = locale.parse::<icu_provider::DataLocale>().ok()?;
+        let strict = Collator::try_new(&data_locale, CollatorOptions::new()).ok()?;
+        let mut options = CollatorOptions::new();
+        options.strength = Some(Strength::Secondary);
+        let ignore_case = Collator::try_new(&data_locale, options).ok()?;
+        Some(Self {
+            locale: KString::from_ref(locale),
+            collators: std::sync::Arc::new(Collators {
+                strict,
+                ignore_case,
+            }),
+        })
+    }
+
+    /// Look up the collation for a locale, like `de` or `en-US`.
+    ///
+    /// Returns `None` for malformed locales or without the `collation` feature.
+    #[cfg(not(feature = "collation"))]
+    pub fn new(_locale: &str) -> Option<Self> {
+        None
+    }
+
+    /// The locale this collation was created for.
+    pub fn locale(&self) -> &str {
+        self.locale.as_str()
+    }
+
+    /// Compare two strings.
+    pub fn compare(&self, lhs: &str, rhs: &str) -> Ordering {
+        #[cfg(feature = "collation")]
+        {
+            self.collators.strict.compare(lhs, rhs)
+        }
+        #[cfg(not(feature = "collation"))]
+        {
+            lhs.cmp(rhs)
+        }
+    }
+
+    /// Compare two strings, treating differences in case as equal.
+    pub fn compare_ignore_case(&self, lhs: &str, rhs: &str) -> Ordering {
+        #[cfg(feature = "collation")]
+        {
+            self.collators.ignore_case.compare(lhs, rhs)
+        }
+        #[cfg(not(feature = "collation"))]
+        {
+            lhs.to_lowercase().cmp(&rhs.to_lowercase())
+        }
+    }
+
+    /// Compare two values if both are strings.
+    pub fn compare_values(&self, lhs: &dyn ValueView, rhs: &dyn ValueView) -> Option<Ordering> {
+        if lhs.type_name() == "string" && rhs.type_name() == "string" {
+            Some(self.compare(lhs.to_kstr().as_str(), rhs.to_kstr().as_str()))
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Collation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.locale)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "collation")]
+    fn test_compare() {
+        let collation = Collation::new("de").unwrap();
+        assert_eq!(collation.locale(), "de");
+        assert_eq!(collation.compare("Äpfel", "Zebra"), Ordering::Less);
+        assert_eq!(collation.compare("apfel", "Apfel"), Ordering::Less);
+        assert_eq!(
+            collation.compare_ignore_case("apfel", "Apfel"),
+            Ordering::Equal
+        );
+        assert!(Collation::new("not a locale!").is_none());
+    }
+
+    #[test]
+    #[cfg(not(feature = "collation"))]
+    fn test_unavailable() {
+        assert!(Collation::new("de").is_none());
+    }
+}
@@ -0,0 +1,264 @@
+//! Helpers for viewing other crates' values.
+//!
+//! Each format classifies its value into an `ExternalValue`, and `external_value_view!` and
+//! `external_object_view!` generate the `ValueView`s from that.
+
+use crate::model::value::DisplayCow;
+use crate::model::{ArrayView, KStringCow, ObjectView, ScalarCow, State, Value, ValueView};
+
+/// What a value from another crate is, borrowing its contents.
+pub(crate) enum ExternalValue<'v> {
+    Nil,
+    Str(&'v String),
+    Scalar(ScalarCow<'v>),
+    Array(&'v dyn ArrayView),
+    Object(&'v dyn ObjectView),
+}
+
+impl<'v> ExternalValue<'v> {
+    pub(crate) fn render(self) -> DisplayCow<'v> {
+        match self {
+            ExternalValue::Nil => DisplayCow::Borrowed(&""),
+            ExternalValue::Str(s) => DisplayCow::Borrowed(s),
+            ExternalValue::Scalar(s) => DisplayCow::Owned(Box::new(s.render().to_string())),
+            ExternalValue::Array(a) => a.render(),
+            ExternalValue::Object(o) => o.render(),
+        }
+    }
+
+    pub(crate) fn source(self) -> DisplayCow<'v> {
+        match self {
+            ExternalValue::Nil => DisplayCow::Borrowed(&"nil"),
+            ExternalValue::Array(a) => a.source(),
+            ExternalValue::Object(o) => o.source(),
+            scalar => DisplayCow::Owned(Box::new(
+                scalar
+                    .into_scalar()
+                    .expect("remaining are scalars")
+                    .source()
+                    .to_string(),
+            )),
+        }
+    }
+
+    pub(crate) fn type_name(self) -> &'static str {
+        match self {
+            ExternalValue::Nil => "nil",
+            ExternalValue::Array(a) => a.type_name(),
+            ExternalValue::Object(o) => o.type_name(),
+            scalar => scalar
+                .into_scalar()
+                .expect("remaining are scalars")
+                .type_name(),
+        }
+    }
+
+    pub(crate) fn query_state(self, state: State) -> bool {
+        match self {
+            ExternalValue::Nil => match state {
+                State::Truthy => false,
+                State::DefaultValue | State::Empty | State::Blank => true,
+            },
+            ExternalValue::Array(a) => a.query_state(state),
+            ExternalValue::Object(o) => o.query_state(state),
+            scalar => scalar
+                .into_scalar()
+                .expect("remaining are scalars")
+                .query_state(state),
+        }
+    }
+
+    pub(crate) fn into_kstr(self) -> KStringCow<'v> {
+        match self {
+            ExternalValue::Nil => KStringCow::from_static(""),
+            ExternalValue::Str(s) => KStringCow::from_ref(s),
+            other => other.render().to_string().into(),
+        }
+    }
+
+    pub(crate) fn into_value(self) -> Value {
+        match self {
+            ExternalValue::Nil => Value::Nil,
+            ExternalValue::Array(a) => a.to_value(),
+            ExternalValue::Object(o) => o.to_value(),
+            scalar => Value::Scalar(
+                scalar
+                    .into_scalar()
+                    .expect("remaining are scalars")
+                    .into_owned(),
+            ),
+        }
+    }
+
+    pub(crate) fn into_scalar(self) -> Option<ScalarCow<'v>> {
+        match self {
+            ExternalValue::Str(s) => Some(ScalarCow::new(s.as_str())),
+            ExternalValue::Scalar(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn into_array(self) -> Option<&'v dyn ArrayView> {
+        match self {
+            ExternalValue::Array(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn into_object(self) -> Option<&'v dyn ObjectView> {
+        match self {
+            ExternalValue::Object(o) => Some(o),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn is_nil(&self) -> bool {
+        matches!(self, ExternalValue::Nil)
+    }
+}
+
+/// Implement `ValueView` for `$value` through `$classify: fn(&$value) -> ExternalValue<'_>`.
+macro_rules! external_value_view {
+    ($value:ty, $classify:path) => {
+        impl $crate::model::ValueView for $value {
+            fn as_debug(&self) -> &dyn ::std::fmt::Debug {
+                self
+            }
+
+            fn render(&self) -> $crate::model::value::DisplayCow<'_> {
+                $classify(self).render()
+            }
+            fn source(&self) -> $crate::model::value::DisplayCow<'_> {
+                $classify(self).source()
+            }
+            fn type_name(&self) -> &'static str {
+                $classify(self).type_name()
+            }
+            fn query_state(&self, state: $crate::model::State) -> bool {
+                $classify(self).query_state(state)
+            }
+
+            fn to_kstr(&self) -> $crate::model::KStringCow<'_> {
+                $classify(self).into_kstr()
+            }
+            fn to_value(&self) -> $crate::model::Value {
+                $classify(self).into_value()
+            }
+
+            fn as_scalar(&self) -> Option<$crate::model::ScalarCow<'_>> {
+                $classify(self).into_scalar()
+            }
+            fn as_array(&self) -> Option<&dyn $crate::model::ArrayView> {
+                $classify(self).into_array()
+            }
+            fn as_object(&self) -> Option<&dyn $crate::model::ObjectView> {
+                $classify(self).into_object()
+            }
+            fn is_nil(&self) -> bool {
+                $classify(self).is_nil()
+            }
+        }
+    };
+}
+
+/// Implement `ValueView` for `$map`, which must implement `ObjectView`.
+///
+/// With `keyed_by_string`, also implement `ObjectView` for maps with `String` keys and an
+/// API like `BTreeMap`'s.
+macro_rules! external_object_view {
+    ($map:ty, keyed_by_string) => {
+        external_object_view!($map);
+
+        impl $crate::model::ObjectView for $map {
+            fn as_value(&self) -> &dyn $crate::model::ValueView {
+                self
+            }
+
+            fn size(&self) -> i64 {
+                self.len() as i64
+            }
+
+            fn keys<'k>(&'k self) -> Box<dyn Iterator<Item = $crate::model::KStringCow<'k>> + 'k> {
+                Box::new(self.keys().map(|k| k.as_str().into()))
+            }
+
+            fn values<'k>(
+                &'k self,
+            ) -> Box<dyn Iterator<Item = &'k dyn $crate::model::ValueView> + 'k> {
+                Box::new(self.values().map(|v| v as &dyn $crate::model::ValueView))
+            }
+
+            fn iter<'k>(
+                &'k self,
+            ) -> Box<
+                dyn Iterator<
+                        Item = (
+                            $crate::model::KStringCow<'k>,
+                            &'k dyn $crate::model::ValueView,
+                        ),
+                    > + 'k,
+            > {
+                Box::new(
+                    self.iter()
+                        .map(|(k, v)| (k.as_str().into(), v as &dyn $crate::model::ValueView)),
+                )
+            }
+
+            fn contains_key(&self, index: &str) -> bool {
+                self.contains_key(index)
+            }
+
+            fn get<'s>(&'s self, index: &str) -> Option<&'s dyn $crate::model::ValueView> {
+                self.get(index).map(|v| v as &dyn $crate::model::ValueView)
+            }
+        }
+    };
+    ($map:ty) => {
+        impl $crate::model::ValueView for $map {
+            fn as_debug(&self) -> &dyn ::std::fmt::Debug {
+                self
+            }
+
+            fn render(&self) -> $crate::model::value::DisplayCow<'_> {
+                $crate::model::value::DisplayCow::Owned(Box::new($crate::model::ObjectRender::new(
+                    self,
+                )))
+            }
+            fn source(&self) -> $crate::model::value::DisplayCow<'_> {
+                $crate::model::value::DisplayCow::Owned(Box::new($crate::model::ObjectSource::new(
+                    self,
+                )))
+            }
+            fn type_name(&self) -> &'static str {
+                "object"
+            }
+            fn query_state(&self, state: $crate::model::State) -> bool {
+                match state {
+                    $crate::model::State::Truthy => true,
+                    $crate::model::State::DefaultValue
+                    | $crate::model::State::Empty
+                    | $crate::model::State::Blank => $crate::model::ObjectView::size(self) == 0,
+                }
+            }
+
+            fn to_kstr(&self) -> $crate::model::KStringCow<'_> {
+                let s = $crate::model::ObjectRender::new(self).to_string();
+                $crate::model::KStringCow::from_string(s)
+            }
+            fn to_value(&self) -> $crate::model::Value {
+                $crate::model::Value::Object(
+                    $crate::model::ObjectView::iter(self)
+                        .map(|(k, v)| (k.into_owned(), v.to_value()))
+                        .collect::<$crate::model::Object>(),
+                )
+            }
+
+            fn as_object(&self) -> Option<&dyn $crate::model::ObjectView> {
+                Some(self)
+            }
+        }
+    };
+}
+
+pub(crate) use external_object_view;
+pub(crate) use external_value_view;
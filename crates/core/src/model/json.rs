@@ -0,0 +1,57 @@
+//! Render `serde_json` values without converting them to `Value`.
+
+use crate::model::ScalarCow;
+
+use super::external::{external_object_view, external_value_view, ExternalValue};
+
+fn classify(value: &serde_json::Value) -> ExternalValue<'_> {
+    match value {
+        serde_json::Value::Null => ExternalValue::Nil,
+        serde_json::Value::Bool(b) => ExternalValue::Scalar(ScalarCow::new(*b)),
+        serde_json::Value::Number(n) => ExternalValue::Scalar(
+            n.as_i64()
+                .map(ScalarCow::new)
+                .or_else(|| n.as_f64().map(ScalarCow::new))
+                .expect("numbers are i64, u64 or f64"),
+        ),
+        serde_json::Value::String(s) => ExternalValue::Str(s),
+        serde_json::Value::Array(a) => ExternalValue::Array(a),
+        serde_json::Value::Object(o) => ExternalValue::Object(o),
+    }
+}
+
+external_value_view!(serde_json::Value, classify);
+external_object_view!(serde_json::Map<String, serde_json::Value>, keyed_by_string);
+
+#[cfg(test)]
+mod test {
+    use crate::model::{ObjectView, ValueView};
+
+    #[test]
+    fn test_json() {
+        let json: serde_json::Value = serde_json::from_str(
+            r#"{"name": "Liquid", "stars": 5, "price": 1.5, "tags": ["a", "b"], "none": null}"#,
+        )
+        .unwrap();
+        let object = json.as_object().unwrap();
+        let view: &dyn ObjectView = object;
+        assert_eq!(view.get("name").unwrap().to_kstr(), "Liquid");
+        assert_eq!(view.get("stars").unwrap().as_scalar().unwrap(), 5i64);
+        assert_eq!(view.get("price").unwrap().render().to_string(), "1.5");
+        assert_eq!(view.get("tags").unwrap().as_array().unwrap().size(), 2);
+        assert!(view.get("none").unwrap().is_nil());
+        assert_eq!(
+            json.to_value(),
+            crate::value!({"name": "Liquid", "stars": 5, "price": 1.5, "tags": ["a", "b"], "none": nil})
+        );
+    }
+
+    #[test]
+    fn test_json_large_unsigned() {
+        let json: serde_json::Value = serde_json::from_str("18446744073709551615").unwrap();
+        let scalar = json.as_scalar().unwrap();
+        assert_eq!(scalar.type_name(), "fractional number");
+        assert_eq!(scalar.to_integer(), None);
+        assert_eq!(scalar.to_float(), Some(18446744073709551615.0));
+    }
+}
@@ -5,16 +5,28 @@
 #![warn(unused_extern_crates)]
 
 mod array;
+mod collation;
+#[cfg(any(feature = "serde_json", feature = "serde_yaml", feature = "toml"))]
+mod external;
 mod find;
+#[cfg(feature = "serde_json")]
+mod json;
 mod object;
+mod query;
 mod scalar;
+#[cfg(feature = "toml")]
+mod toml;
 mod value;
+#[cfg(feature = "serde_yaml")]
+mod yaml;
 
 mod ser;
 
 pub use array::*;
+pub use collation::*;
 pub use find::*;
 pub use object::*;
+pub use query::*;
 pub use scalar::*;
 pub use value::*;
 
@@ -1,6 +1,7 @@
 //! Type representing a Liquid object, payload of the `Value::Object` variant
 
 use std::borrow::Borrow;
+#[cfg(not(feature = "preserve_order"))]
 use std::collections::hash_map;
 use std::fmt::{self, Debug};
 use std::hash::Hash;
@@ -12,6 +13,9 @@ use serde::{de, ser};
 use super::Value;
 
 /// Type representing a Liquid object, payload of the `Value::Object` variant
+///
+/// Iteration order is unspecified unless the `preserve_order` feature is enabled, in which case
+/// entries are kept in insertion order.
 #[derive(Default, Clone, PartialEq, Eq)]
 pub struct Object {
     map: MapImpl<Key, Value>,
@@ -19,16 +23,48 @@ pub struct Object {
 
 type Key = crate::model::KString;
 
+#[cfg(not(feature = "preserve_order"))]
 type MapImpl<K, V> = hash_map::HashMap<K, V>;
+#[cfg(not(feature = "preserve_order"))]
+type EntryImpl<'a> = hash_map::Entry<'a, Key, Value>;
+#[cfg(not(feature = "preserve_order"))]
 type VacantEntryImpl<'a> = hash_map::VacantEntry<'a, Key, Value>;
+#[cfg(not(feature = "preserve_order"))]
 type OccupiedEntryImpl<'a> = hash_map::OccupiedEntry<'a, Key, Value>;
+#[cfg(not(feature = "preserve_order"))]
 type IterImpl<'a> = hash_map::Iter<'a, Key, Value>;
+#[cfg(not(feature = "preserve_order"))]
 type IterMutImpl<'a> = hash_map::IterMut<'a, Key, Value>;
+#[cfg(not(feature = "preserve_order"))]
 type IntoIterImpl = hash_map::IntoIter<Key, Value>;
+#[cfg(not(feature = "preserve_order"))]
 type KeysImpl<'a> = hash_map::Keys<'a, Key, Value>;
+#[cfg(not(feature = "preserve_order"))]
 type ValuesImpl<'a> = hash_map::Values<'a, Key, Value>;
+#[cfg(not(feature = "preserve_order"))]
 type ValuesMutImpl<'a> = hash_map::ValuesMut<'a, Key, Value>;
 
+#[cfg(feature = "preserve_order")]
+type MapImpl<K, V> = indexmap::IndexMap<K, V>;
+#[cfg(feature = "preserve_order")]
+type EntryImpl<'a> = indexmap::map::Entry<'a, Key, Value>;
+#[cfg(feature = "preserve_order")]
+type VacantEntryImpl<'a> = indexmap::map::VacantEntry<'a, Key, Value>;
+#[cfg(feature = "preserve_order")]
+type OccupiedEntryImpl<'a> = indexmap::map::OccupiedEntry<'a, Key, Value>;
+#[cfg(feature = "preserve_order")]
+type IterImpl<'a> = indexmap::map::Iter<'a, Key, Value>;
+#[cfg(feature = "preserve_order")]
+type IterMutImpl<'a> = indexmap::map::IterMut<'a, Key, Value>;
+#[cfg(feature = "preserve_order")]
+type IntoIterImpl = indexmap::map::IntoIter<Key, Value>;
+#[cfg(feature = "preserve_order")]
+type KeysImpl<'a> = indexmap::map::Keys<'a, Key, Value>;
+#[cfg(feature = "preserve_order")]
+type ValuesImpl<'a> = indexmap::map::Values<'a, Key, Value>;
+#[cfg(feature = "preserve_order")]
+type ValuesMutImpl<'a> = indexmap::map::ValuesMut<'a, Key, Value>;
+
 impl Object {
     /// Makes a new empty Object.
     #[inline]
@@ -105,7 +141,14 @@ impl Object {
         Key: Borrow<Q>,
         Q: Ord + Eq + Hash,
     {
-        self.map.remove(key)
+        #[cfg(not(feature = "preserve_order"))]
+        {
+            self.map.remove(key)
+        }
+        #[cfg(feature = "preserve_order")]
+        {
+            self.map.shift_remove(key)
+        }
     }
 
     /// Gets the given key's corresponding entry in the map for in-place
@@ -114,7 +157,6 @@ impl Object {
     where
         S: Into<Key>,
     {
-        use std::collections::hash_map::Entry as EntryImpl;
         match self.map.entry(key.into()) {
             EntryImpl::Vacant(vacant) => Entry::Vacant(VacantEntry { vacant }),
             EntryImpl::Occupied(occupied) => Entry::Occupied(OccupiedEntry { occupied }),
@@ -597,7 +639,14 @@ impl<'a> OccupiedEntry<'a> {
     /// ```
     #[inline]
     pub fn remove(self) -> Value {
-        self.occupied.remove()
+        #[cfg(not(feature = "preserve_order"))]
+        {
+            self.occupied.remove()
+        }
+        #[cfg(feature = "preserve_order")]
+        {
+            self.occupied.shift_remove()
+        }
     }
 }
 
@@ -371,4 +371,21 @@ mod test {
         let view: &dyn ValueView = object.as_value();
         println!("{}", view.source());
     }
+
+    #[test]
+    #[cfg(feature = "preserve_order")]
+    fn test_insertion_order() {
+        let mut obj = Object::new();
+        for key in ["zebra", "apple", "mango", "kiwi"] {
+            obj.insert(key.into(), Value::scalar(key.len() as i64));
+        }
+        obj.remove("apple");
+        obj.insert("banana".into(), Value::scalar(6));
+        obj.insert("zebra".into(), Value::scalar(0));
+
+        let keys: Vec<_> = ObjectView::keys(&obj).collect();
+        assert_eq!(keys, ["zebra", "mango", "kiwi", "banana"]);
+        let values: Vec<_> = ObjectView::values(&obj).map(|v| v.to_value()).collect();
+        assert_eq!(values, [value!(0), value!(5), value!(4), value!(6)]);
+    }
 }
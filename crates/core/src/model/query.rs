@@ -0,0 +1,516 @@
+//! Select the `ValueView`s matching a JSONPath-like query
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+use crate::model::KString;
+
+use super::Value;
+use super::ValueView;
+use super::ValueViewCmp;
+
+/// A compiled JSONPath-like query.
+///
+/// Supports:
+/// - `$` for the root, which may be left out: `$.items` and `items` are the same
+/// - `.name` and `['name']` for object members
+/// - `[0]` and `[-1]` for array elements, counting back from the end when negative
+/// - `[1:3]`, `[:2]` and `[-2:]` for array slices
+/// - `*` and `[*]` for every member or element
+/// - `..name` for every `name` member at any depth, and `..*` for every nested value
+/// - `[?(@.available)]` for the members or elements where the relative path is truthy, and
+///   `[?(@.price < 10)]` to compare it with a literal using `==`, `!=`, `<`, `<=`, `>` or `>=`
+///
+/// # Example
+///
+/// ```rust
+/// let data = liquid_core::value!({
+///     "items": [
+///         {"title": "Tea", "price": 3, "available": true},
+///         {"title": "Cake", "price": 12, "available": false},
+///         {"title": "Coffee", "price": 4, "available": true},
+///     ]
+/// });
+/// let titles = liquid_core::model::Query::parse("$.items[?(@.available)].title").unwrap();
+/// let titles: Vec<_> = titles.select(&data).into_iter().map(|v| v.to_kstr()).collect();
+/// assert_eq!(titles, ["Tea", "Coffee"]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query {
+    source: String,
+    segments: Vec<Segment>,
+}
+
+impl Query {
+    /// Compile a query.
+    pub fn parse(query: &str) -> Result<Self> {
+        let mut parser = QueryParser { query, pos: 0 };
+        let segments = parser.parse_root()?;
+        Ok(Self {
+            source: query.to_owned(),
+            segments,
+        })
+    }
+
+    /// The values matching this query, in document order.
+    pub fn select<'v>(&self, value: &'v dyn ValueView) -> Vec<&'v dyn ValueView> {
+        select(&self.segments, value)
+    }
+}
+
+impl FromStr for Query {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+/// Select the `ValueView`s matching a JSONPath-like `query`.
+///
+/// See [`Query`] for the supported syntax.  Like [`find`](super::find), this is a function
+/// rather than a `ValueView` method, so `ValueView` stays free of methods that implementors
+/// never override.  Use [`Query::parse`] instead when running the same query more than once.
+pub fn query<'v>(value: &'v dyn ValueView, query: &str) -> Result<Vec<&'v dyn ValueView>> {
+    Query::parse(query).map(|q| q.select(value))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Child(Selector),
+    Descendant(Selector),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Selector {
+    Name(KString),
+    Index(i64),
+    Wildcard,
+    Slice(Option<i64>, Option<i64>),
+    Filter(Predicate),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Predicate {
+    path: Vec<Segment>,
+    comparison: Option<(Comparison, Value)>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Comparison {
+    Equals,
+    NotEquals,
+    LessThan,
+    LessThanEquals,
+    GreaterThan,
+    GreaterThanEquals,
+}
+
+fn select<'v>(segments: &[Segment], value: &'v dyn ValueView) -> Vec<&'v dyn ValueView> {
+    let mut current = vec![value];
+    for segment in segments {
+        let mut next = Vec::new();
+        for value in current {
+            match segment {
+                Segment::Child(selector) => selector.apply(value, &mut next),
+                Segment::Descendant(selector) => {
+                    let mut nested = vec![value];
+                    collect_descendants(value, &mut nested);
+                    for value in nested {
+                        selector.apply(value, &mut next);
+                    }
+                }
+            }
+        }
+        current = next;
+    }
+    current
+}
+
+fn children(value: &dyn ValueView) -> Vec<&dyn ValueView> {
+    if let Some(arr) = value.as_array() {
+        arr.values().collect()
+    } else if let Some(obj) = value.as_object() {
+        obj.values().collect()
+    } else {
+        Vec::new()
+    }
+}
+
+fn collect_descendants<'v>(value: &'v dyn ValueView, out: &mut Vec<&'v dyn ValueView>) {
+    for child in children(value) {
+        out.push(child);
+        collect_descendants(child, out);
+    }
+}
+
+impl Selector {
+    fn apply<'v>(&self, value: &'v dyn ValueView, out: &mut Vec<&'v dyn ValueView>) {
+        match self {
+            Selector::Name(name) => {
+                if let Some(child) = value.as_object().and_then(|obj| obj.get(name.as_str())) {
+                    out.push(child);
+                }
+            }
+            Selector::Index(index) => {
+                if let Some(child) = value.as_array().and_then(|arr| arr.get(*index)) {
+                    out.push(child);
+                }
+            }
+            Selector::Wildcard => out.extend(children(value)),
+            Selector::Slice(start, end) => {
+                if let Some(arr) = value.as_array() {
+                    let size = arr.size();
+                    let clamp = |index: i64| {
+                        let index = if index < 0 { size + index } else { index };
+                        index.clamp(0, size)
+                    };
+                    let start = start.map(clamp).unwrap_or(0);
+                    let end = end.map(clamp).unwrap_or(size);
+                    out.extend((start..end).filter_map(|i| arr.get(i)));
+                }
+            }
+            Selector::Filter(predicate) => out.extend(
+                children(value)
+                    .into_iter()
+                    .filter(|child| predicate.matches(*child)),
+            ),
+        }
+    }
+}
+
+impl Predicate {
+    fn matches(&self, value: &dyn ValueView) -> bool {
+        let found = match select(&self.path, value).first() {
+            Some(found) => *found,
+            None => return false,
+        };
+        match &self.comparison {
+            None => found.query_state(super::State::Truthy),
+            Some((comparison, literal)) => {
+                let found = ValueViewCmp::new(found);
+                let literal = ValueViewCmp::new(literal);
+                match comparison {
+                    Comparison::Equals => found == literal,
+                    Comparison::NotEquals => found != literal,
+                    Comparison::LessThan => found.partial_cmp(&literal) == Some(Ordering::Less),
+                    Comparison::LessThanEquals => matches!(
+                        found.partial_cmp(&literal),
+                        Some(Ordering::Less | Ordering::Equal)
+                    ),
+                    Comparison::GreaterThan => {
+                        found.partial_cmp(&literal) == Some(Ordering::Greater)
+                    }
+                    Comparison::GreaterThanEquals => matches!(
+                        found.partial_cmp(&literal),
+                        Some(Ordering::Greater | Ordering::Equal)
+                    ),
+                }
+            }
+        }
+    }
+}
+
+struct QueryParser<'q> {
+    query: &'q str,
+    pos: usize,
+}
+
+impl<'q> QueryParser<'q> {
+    fn parse_root(&mut self) -> Result<Vec<Segment>> {
+        self.skip_whitespace();
+        let mut segments = Vec::new();
+        if !self.eat("$") && !self.rest().starts_with(['.', '[']) {
+            // A bare `items.price`, as in a Liquid variable.
+            let name = self.parse_name()?;
+            segments.push(Segment::Child(Selector::Name(name)));
+        }
+        segments.extend(self.parse_segments()?);
+        self.skip_whitespace();
+        if !self.rest().is_empty() {
+            return Err(self.error("Unexpected character"));
+        }
+        Ok(segments)
+    }
+
+    /// Parses segments until the end of the query or something that can't start one.
+    fn parse_segments(&mut self) -> Result<Vec<Segment>> {
+        let mut segments = Vec::new();
+        loop {
+            if self.eat("..") {
+                let selector = if self.rest().starts_with('[') {
+                    self.parse_bracket()?
+                } else {
+                    self.parse_dot_selector()?
+                };
+                segments.push(Segment::Descendant(selector));
+            } else if self.eat(".") {
+                segments.push(Segment::Child(self.parse_dot_selector()?));
+            } else if self.rest().starts_with('[') {
+                segments.push(Segment::Child(self.parse_bracket()?));
+            } else {
+                return Ok(segments);
+            }
+        }
+    }
+
+    fn parse_dot_selector(&mut self) -> Result<Selector> {
+        if self.eat("*") {
+            Ok(Selector::Wildcard)
+        } else {
+            self.parse_name().map(Selector::Name)
+        }
+    }
+
+    fn parse_name(&mut self) -> Result<KString> {
+        let len = self
+            .rest()
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(self.rest().len());
+        if len == 0 {
+            return Err(self.error("Name expected"));
+        }
+        let name = KString::from_ref(&self.rest()[..len]);
+        self.pos += len;
+        Ok(name)
+    }
+
+    fn parse_bracket(&mut self) -> Result<Selector> {
+        self.expect("[")?;
+        self.skip_whitespace();
+        let selector = if self.eat("*") {
+            Selector::Wildcard
+        } else if self.eat("?") {
+            self.skip_whitespace();
+            self.expect("(")?;
+            let predicate = self.parse_predicate()?;
+            self.expect(")")?;
+            Selector::Filter(predicate)
+        } else if self.rest().starts_with(['\'', '"']) {
+            Selector::Name(KString::from_ref(self.parse_string()?))
+        } else {
+            let start = self.parse_integer()?;
+            self.skip_whitespace();
+            if self.eat(":") {
+                self.skip_whitespace();
+                let end = self.parse_integer()?;
+                Selector::Slice(start, end)
+            } else {
+                let index = start.ok_or_else(|| self.error("Index expected"))?;
+                Selector::Index(index)
+            }
+        };
+        self.skip_whitespace();
+        self.expect("]")?;
+        Ok(selector)
+    }
+
+    fn parse_predicate(&mut self) -> Result<Predicate> {
+        self.skip_whitespace();
+        self.expect("@")?;
+        let path = self.parse_segments()?;
+        self.skip_whitespace();
+        let comparison = [
+            ("==", Comparison::Equals),
+            ("!=", Comparison::NotEquals),
+            ("<=", Comparison::LessThanEquals),
+            (">=", Comparison::GreaterThanEquals),
+            ("<", Comparison::LessThan),
+            (">", Comparison::GreaterThan),
+        ]
+        .into_iter()
+        .find(|(op, _)| self.eat(op));
+        let comparison = match comparison {
+            Some((_, comparison)) => {
+                self.skip_whitespace();
+                Some((comparison, self.parse_literal()?))
+            }
+            None => None,
+        };
+        self.skip_whitespace();
+        Ok(Predicate { path, comparison })
+    }
+
+    fn parse_literal(&mut self) -> Result<Value> {
+        if self.rest().starts_with(['\'', '"']) {
+            return Ok(Value::scalar(self.parse_string()?.to_owned()));
+        }
+        let len = self
+            .rest()
+            .find(|c: char| !(c.is_alphanumeric() || c == '.' || c == '-' || c == '+'))
+            .unwrap_or(self.rest().len());
+        let literal = &self.rest()[..len];
+        let value = match literal {
+            "true" => Value::scalar(true),
+            "false" => Value::scalar(false),
+            "nil" | "null" => Value::Nil,
+            _ => {
+                if let Ok(i) = literal.parse::<i64>() {
+                    Value::scalar(i)
+                } else if let Ok(f) = literal.parse::<f64>() {
+                    Value::scalar(f)
+                } else {
+                    return Err(self.error("Literal expected"));
+                }
+            }
+        };
+        self.pos += len;
+        Ok(value)
+    }
+
+    fn parse_string(&mut self) -> Result<&'q str> {
+        let rest = self.rest();
+        let quote = rest
+            .chars()
+            .next()
+            .ok_or_else(|| self.error("String expected"))?;
+        let len = rest[1..]
+            .find(quote)
+            .ok_or_else(|| self.error("Unterminated string"))?;
+        let s = &rest[1..=len];
+        self.pos += len + 2;
+        Ok(s)
+    }
+
+    fn parse_integer(&mut self) -> Result<Option<i64>> {
+        let rest = self.rest();
+        let len = rest
+            .char_indices()
+            .find(|&(i, c)| !(c.is_ascii_digit() || (i == 0 && c == '-')))
+            .map(|(i, _)| i)
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Ok(None);
+        }
+        let i = rest[..len]
+            .parse()
+            .map_err(|_| self.error("Integer expected"))?;
+        self.pos += len;
+        Ok(Some(i))
+    }
+
+    fn rest(&self) -> &'q str {
+        &self.query[self.pos..]
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        if self.rest().starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, s: &str) -> Result<()> {
+        if self.eat(s) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("`{}` expected", s)))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn error(&self, msg: &str) -> Error {
+        Error::with_msg(format!("Invalid query: {}", msg))
+            .context("query", self.query.to_owned())
+            .context("position", self.pos.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn titles(value: &Value, q: &str) -> Vec<String> {
+        query(value, q)
+            .unwrap()
+            .into_iter()
+            .map(|v| v.to_kstr().to_string())
+            .collect()
+    }
+
+    fn store() -> Value {
+        crate::value!({
+            "store": {
+                "items": [
+                    {"title": "Tea", "price": 3, "available": true, "tags": ["hot"]},
+                    {"title": "Cake", "price": 12, "available": false, "tags": ["sweet"]},
+                    {"title": "Coffee", "price": 4, "available": true},
+                ],
+                "featured": {"title": "Scone", "tags": ["sweet", "baked"]},
+            }
+        })
+    }
+
+    #[test]
+    fn test_paths() {
+        let store = store();
+        assert_eq!(titles(&store, "$.store.featured.title"), ["Scone"]);
+        assert_eq!(titles(&store, "store['featured']['title']"), ["Scone"]);
+        assert_eq!(titles(&store, "store.items[-1].title"), ["Coffee"]);
+        assert!(titles(&store, "store.missing.title").is_empty());
+    }
+
+    #[test]
+    fn test_wildcards() {
+        let store = store();
+        assert_eq!(
+            titles(&store, "store.items[*].title"),
+            ["Tea", "Cake", "Coffee"]
+        );
+        assert_eq!(titles(&store, "store.items.*.price"), ["3", "12", "4"]);
+        assert_eq!(titles(&store, "store.items[1:].title"), ["Cake", "Coffee"]);
+        assert_eq!(titles(&store, "store.items[:-2].title"), ["Tea"]);
+    }
+
+    #[test]
+    fn test_descendants() {
+        let store = store();
+        let mut tags = titles(&store, "$..tags[*]");
+        tags.sort();
+        assert_eq!(tags, ["baked", "hot", "sweet", "sweet"]);
+        assert_eq!(titles(&store, "$..items[0].title"), ["Tea"]);
+    }
+
+    #[test]
+    fn test_filters() {
+        let store = store();
+        assert_eq!(
+            titles(&store, "$.store.items[?(@.available)].title"),
+            ["Tea", "Coffee"]
+        );
+        assert_eq!(
+            titles(&store, "$.store.items[?(@.price < 10)].title"),
+            ["Tea", "Coffee"]
+        );
+        assert_eq!(
+            titles(&store, "$.store.items[?(@.title == 'Cake')].price"),
+            ["12"]
+        );
+        assert_eq!(
+            titles(&store, "$.store.items[?(@.tags[0] != \"hot\")].title"),
+            ["Cake"]
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Query::parse("$.items[").is_err());
+        assert!(Query::parse("$.items[?(@.price <)]").is_err());
+        assert!(Query::parse("$.items]").is_err());
+        assert!(Query::parse("$.").is_err());
+    }
+}
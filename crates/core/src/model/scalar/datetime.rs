@@ -5,6 +5,7 @@ use std::ops;
 mod strftime;
 
 use super::Date;
+use super::TimeZone;
 
 /// Liquid's native date + time type.
 #[derive(
@@ -14,7 +15,7 @@ use super::Date;
 #[repr(transparent)]
 pub struct DateTime {
     #[serde(with = "friendly_date_time")]
-    inner: DateTimeImpl,
+    pub(crate) inner: DateTimeImpl,
 }
 
 type DateTimeImpl = time::OffsetDateTime;
@@ -44,7 +45,29 @@ impl DateTime {
         }
     }
 
+    /// Create a `DateTime` from seconds since the Unix epoch.
+    pub fn from_unix_timestamp(seconds: i64) -> Option<Self> {
+        DateTimeImpl::from_unix_timestamp(seconds)
+            .ok()
+            .map(|inner| Self { inner })
+    }
+
+    /// Create a `DateTime` from fractional seconds since the Unix epoch.
+    pub fn from_unix_timestamp_f64(seconds: f64) -> Option<Self> {
+        if !seconds.is_finite() {
+            return None;
+        }
+        let nanos = (seconds * 1_000_000_000.0).round() as i128;
+        DateTimeImpl::from_unix_timestamp_nanos(nanos)
+            .ok()
+            .map(|inner| Self { inner })
+    }
+
     /// Convert a `str` to `Self`
+    ///
+    /// Besides `DateTime`'s own format, this accepts ISO 8601 / RFC 3339 (like
+    /// `2016-02-16T10:00:00Z`), RFC 2822, seconds since the Unix epoch and a few other common
+    /// formats.  Without an offset, UTC is assumed.
     #[allow(clippy::should_implement_trait)]
     pub fn from_str(other: &str) -> Option<Self> {
         parse_date_time(other).map(|d| Self { inner: d })
@@ -64,6 +87,12 @@ impl DateTime {
         }
     }
 
+    /// Changes the time zone, following daylight saving time for named time zones. This does not
+    /// change the actual DateTime (but will change the string representation).
+    pub fn with_timezone(self, tz: TimeZone) -> Self {
+        self.with_offset(tz.offset_at(self))
+    }
+
     /// Retrieves a date component.
     pub fn date(self) -> Date {
         Date {
@@ -237,6 +266,13 @@ fn parse_date_time(s: &str) -> Option<DateTimeImpl> {
         None
     } else if let "now" | "today" = s.to_lowercase().trim() {
         Some(DateTimeImpl::now_utc())
+    } else if let Some(d) = parse_well_known(s) {
+        Some(d)
+    } else if s.bytes().all(|b| b.is_ascii_digit()) {
+        // Like Ruby, a string of digits is seconds since the Unix epoch
+        s.parse()
+            .ok()
+            .and_then(|s| DateTimeImpl::from_unix_timestamp(s).ok())
     } else {
         let offset_re = Regex::new(r"[+-][01][0-9]{3}$").unwrap();
 
@@ -249,6 +285,16 @@ fn parse_date_time(s: &str) -> Option<DateTimeImpl> {
     }
 }
 
+fn parse_well_known(s: &str) -> Option<DateTimeImpl> {
+    use time::format_description::well_known::{Iso8601, Rfc2822, Rfc3339};
+
+    DateTimeImpl::parse(s, &Rfc3339)
+        .or_else(|_| DateTimeImpl::parse(s, &Iso8601::DEFAULT))
+        .or_else(|_| time::PrimitiveDateTime::parse(s, &Iso8601::DEFAULT).map(|d| d.assume_utc()))
+        .or_else(|_| DateTimeImpl::parse(s, &Rfc2822))
+        .ok()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -375,6 +421,57 @@ mod test {
         assert!(actual.unwrap().unix_timestamp() == 1455616800);
     }
 
+    #[test]
+    fn parse_date_time_rfc3339() {
+        let input = "2016-02-16T10:00:00Z";
+        let actual = parse_date_time(input);
+        assert!(actual.unwrap().unix_timestamp() == 1455616800);
+
+        let input = "2016-02-16T10:00:00+01:00";
+        let actual = parse_date_time(input);
+        assert!(actual.unwrap().unix_timestamp() == 1455613200);
+
+        let input = "2016-02-16T10:00:00.123Z"; // fractional seconds
+        let actual = parse_date_time(input);
+        assert!(actual.unwrap().unix_timestamp_nanos() == 1455616800123000000);
+    }
+
+    #[test]
+    fn parse_date_time_iso8601() {
+        let input = "2016-02-16T10:00:00"; // no offset
+        let actual = parse_date_time(input);
+        assert!(actual.unwrap().unix_timestamp() == 1455616800);
+
+        let input = "2016-02-16T10:00+01:00"; // no seconds
+        let actual = parse_date_time(input);
+        assert!(actual.unwrap().unix_timestamp() == 1455613200);
+
+        let input = "20160216T100000Z"; // basic format
+        let actual = parse_date_time(input);
+        assert!(actual.unwrap().unix_timestamp() == 1455616800);
+    }
+
+    #[test]
+    fn parse_date_time_rfc2822() {
+        let input = "Tue, 16 Feb 2016 10:00:00 +0100";
+        let actual = parse_date_time(input);
+        assert!(actual.unwrap().unix_timestamp() == 1455613200);
+
+        let input = "Tue, 16 Feb 2016 10:00:00 GMT";
+        let actual = parse_date_time(input);
+        assert!(actual.unwrap().unix_timestamp() == 1455616800);
+    }
+
+    #[test]
+    fn parse_date_time_epoch() {
+        let input = "1455616800";
+        let actual = parse_date_time(input);
+        assert!(actual.unwrap().unix_timestamp() == 1455616800);
+
+        let actual = DateTime::from_unix_timestamp_f64(1455616800.5).unwrap();
+        assert!(actual.inner.unix_timestamp_nanos() == 1455616800500000000);
+    }
+
     #[test]
     fn parse_date_time_to_string() {
         let date = DateTime::now();
@@ -0,0 +1,85 @@
+use std::borrow::Cow;
+
+/// HTML-escape `text`, like the `escape` filter and auto-escaping.
+pub fn escape_html(text: &str) -> Cow<'_, str> {
+    escape(text, false)
+}
+
+/// HTML-escape `text`, leaving existing entities like `&amp;` as-is.
+pub fn escape_html_once(text: &str) -> Cow<'_, str> {
+    escape(text, true)
+}
+
+/// Returns the number of already escaped characters.
+fn nr_escaped(text: &str) -> usize {
+    for prefix in &["lt;", "gt;", "#39;", "quot;", "amp;"] {
+        if text.starts_with(prefix) {
+            return prefix.len();
+        }
+    }
+    0
+}
+
+// The code is adapted from
+// https://github.com/rust-lang/rust/blob/master/src/librustdoc/html/escape.rs
+// Retrieved 2016-11-19.
+fn escape(s: &str, once_p: bool) -> Cow<'_, str> {
+    if !s.contains(['<', '>', '\'', '"', '&']) {
+        return Cow::Borrowed(s);
+    }
+    let mut result = String::with_capacity(s.len());
+    let mut last = 0;
+    let mut skip = 0;
+    for (i, c) in s.char_indices() {
+        if skip > 0 {
+            skip -= 1;
+            continue;
+        }
+        match c {
+            '<' | '>' | '\'' | '"' | '&' => {
+                result.push_str(&s[last..i]);
+                last = i + 1;
+                let escaped = match c {
+                    '<' => "&lt;",
+                    '>' => "&gt;",
+                    '\'' => "&#39;",
+                    '"' => "&quot;",
+                    '&' => {
+                        if once_p {
+                            skip = nr_escaped(&s[last..]);
+                        }
+                        if skip == 0 {
+                            "&amp;"
+                        } else {
+                            "&"
+                        }
+                    }
+                    _ => unreachable!(),
+                };
+                result.push_str(escaped);
+            }
+            _ => {}
+        }
+    }
+    if last < s.len() {
+        result.push_str(&s[last..]);
+    }
+    Cow::Owned(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(escape_html("plain"), "plain");
+        assert!(matches!(escape_html("plain"), Cow::Borrowed(_)));
+        assert_eq!(
+            escape_html("<a href=\"x\">Tom & 'Jerry'</a>"),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &#39;Jerry&#39;&lt;/a&gt;"
+        );
+        assert_eq!(escape_html("&lt;"), "&amp;lt;");
+        assert_eq!(escape_html_once("&lt; & <"), "&lt; &amp; &lt;");
+    }
+}
@@ -4,7 +4,9 @@
 
 mod date;
 mod datetime;
+mod escape;
 pub(crate) mod ser;
+mod timezone;
 
 use std::cmp::Ordering;
 use std::{borrow::Cow, fmt};
@@ -18,7 +20,18 @@ use crate::model::{Value, ValueView};
 
 pub use date::*;
 pub use datetime::*;
+pub use escape::*;
 pub use ser::to_scalar;
+pub use timezone::*;
+
+/// An exact decimal number, for money and other amounts that must not pick up float artifacts.
+///
+/// Supply them as `Value::scalar(decimal)` or through `ValueView`.  Serde serializes them as
+/// strings, and deserializing, including `to_value` and `to_object`, never produces a decimal:
+/// numbers become integers or floats and `"1.10"` stays a string.  Data that needs exact amounts
+/// has to build its `Value`s directly.
+#[cfg(feature = "decimal")]
+pub use rust_decimal::Decimal;
 
 /// A Liquid scalar value
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
@@ -39,6 +52,12 @@ enum ScalarCowEnum<'s> {
     DateTime(DateTime),
     Date(Date),
     Str(KStringCow<'s>),
+    SafeStr(KStringCow<'s>),
+    TaintedStr(KStringCow<'s>),
+    // Serialized as a string to not lose precision.  Last so strings still deserialize as `Str`.
+    #[cfg(feature = "decimal")]
+    #[serde(serialize_with = "ser::serialize_decimal", skip_deserializing)]
+    Decimal(Decimal),
 }
 
 impl<'s> ScalarCow<'s> {
@@ -47,6 +66,50 @@ impl<'s> ScalarCow<'s> {
         value.into()
     }
 
+    /// Create a string that is safe to output without HTML-escaping.
+    ///
+    /// Auto-escaping passes these through as-is, so only use this for trusted HTML.
+    pub fn safe<T: Into<KStringCow<'s>>>(value: T) -> Self {
+        ScalarCow(ScalarCowEnum::SafeStr(value.into()))
+    }
+
+    /// Whether this is a string that is safe to output without HTML-escaping.
+    pub fn is_safe(&self) -> bool {
+        matches!(self.0, ScalarCowEnum::SafeStr(_))
+    }
+
+    /// Create a string from an untrusted source.
+    ///
+    /// When checking for taint, this must be escaped before it is output.
+    pub fn tainted<T: Into<KStringCow<'s>>>(value: T) -> Self {
+        ScalarCow(ScalarCowEnum::TaintedStr(value.into()))
+    }
+
+    /// Whether this is a string from an untrusted source.
+    pub fn is_tainted(&self) -> bool {
+        matches!(self.0, ScalarCowEnum::TaintedStr(_))
+    }
+
+    /// Whether this is a string, of any kind.
+    pub(crate) fn is_str(&self) -> bool {
+        matches!(
+            self.0,
+            ScalarCowEnum::Str(_) | ScalarCowEnum::SafeStr(_) | ScalarCowEnum::TaintedStr(_)
+        )
+    }
+
+    /// Mark a string as coming from an untrusted source.
+    ///
+    /// Taint takes precedence over safety: a safe string stops being safe, so auto-escaping and
+    /// taint checks treat it like any other untrusted string.  Other kinds of scalars are returned
+    /// as-is.
+    pub fn into_tainted(self) -> Self {
+        match self.0 {
+            ScalarCowEnum::Str(x) | ScalarCowEnum::SafeStr(x) => ScalarCow::tainted(x),
+            _ => self,
+        }
+    }
+
     /// Create an owned version of the value.
     pub fn into_owned(self) -> Scalar {
         match self.0 {
@@ -56,6 +119,10 @@ impl<'s> ScalarCow<'s> {
             ScalarCowEnum::DateTime(x) => Scalar::new(x),
             ScalarCowEnum::Date(x) => Scalar::new(x),
             ScalarCowEnum::Str(x) => Scalar::new(x.into_owned()),
+            ScalarCowEnum::SafeStr(x) => Scalar::safe(x.into_owned()),
+            ScalarCowEnum::TaintedStr(x) => Scalar::tainted(x.into_owned()),
+            #[cfg(feature = "decimal")]
+            ScalarCowEnum::Decimal(x) => Scalar::new(x),
         }
     }
 
@@ -68,6 +135,10 @@ impl<'s> ScalarCow<'s> {
             ScalarCowEnum::DateTime(x) => ScalarCow::new(x),
             ScalarCowEnum::Date(x) => ScalarCow::new(x),
             ScalarCowEnum::Str(ref x) => ScalarCow::new(x.as_ref()),
+            ScalarCowEnum::SafeStr(ref x) => ScalarCow::safe(x.as_ref()),
+            ScalarCowEnum::TaintedStr(ref x) => ScalarCow::tainted(x.as_ref()),
+            #[cfg(feature = "decimal")]
+            ScalarCowEnum::Decimal(x) => ScalarCow::new(x),
         }
     }
 
@@ -79,7 +150,11 @@ impl<'s> ScalarCow<'s> {
             ScalarCowEnum::Bool(ref x) => x,
             ScalarCowEnum::DateTime(ref x) => x,
             ScalarCowEnum::Date(ref x) => x,
-            ScalarCowEnum::Str(ref x) => x,
+            ScalarCowEnum::Str(ref x)
+            | ScalarCowEnum::SafeStr(ref x)
+            | ScalarCowEnum::TaintedStr(ref x) => x,
+            #[cfg(feature = "decimal")]
+            ScalarCowEnum::Decimal(ref x) => x,
         }
     }
 
@@ -91,7 +166,11 @@ impl<'s> ScalarCow<'s> {
             ScalarCowEnum::Bool(x) => x.to_string().into(),
             ScalarCowEnum::DateTime(x) => x.to_string().into(),
             ScalarCowEnum::Date(x) => x.to_string().into(),
-            ScalarCowEnum::Str(x) => x.into_owned(),
+            ScalarCowEnum::Str(x) | ScalarCowEnum::SafeStr(x) | ScalarCowEnum::TaintedStr(x) => {
+                x.into_owned()
+            }
+            #[cfg(feature = "decimal")]
+            ScalarCowEnum::Decimal(x) => x.to_string().into(),
         }
     }
 
@@ -99,7 +178,13 @@ impl<'s> ScalarCow<'s> {
     pub fn to_integer(&self) -> Option<i64> {
         match self.0 {
             ScalarCowEnum::Integer(ref x) => Some(*x),
-            ScalarCowEnum::Str(ref x) => x.parse::<i64>().ok(),
+            ScalarCowEnum::Str(ref x)
+            | ScalarCowEnum::SafeStr(ref x)
+            | ScalarCowEnum::TaintedStr(ref x) => x.parse::<i64>().ok(),
+            #[cfg(feature = "decimal")]
+            ScalarCowEnum::Decimal(ref x) if x.fract().is_zero() => {
+                num_traits::ToPrimitive::to_i64(x)
+            }
             _ => None,
         }
     }
@@ -109,7 +194,33 @@ impl<'s> ScalarCow<'s> {
         match self.0 {
             ScalarCowEnum::Integer(ref x) => Some(*x as f64),
             ScalarCowEnum::Float(ref x) => Some(*x),
-            ScalarCowEnum::Str(ref x) => x.parse::<f64>().ok(),
+            ScalarCowEnum::Str(ref x)
+            | ScalarCowEnum::SafeStr(ref x)
+            | ScalarCowEnum::TaintedStr(ref x) => x.parse::<f64>().ok(),
+            #[cfg(feature = "decimal")]
+            ScalarCowEnum::Decimal(ref x) => num_traits::ToPrimitive::to_f64(x),
+            _ => None,
+        }
+    }
+
+    /// Whether this is an exact decimal.
+    #[cfg(feature = "decimal")]
+    pub fn is_decimal(&self) -> bool {
+        matches!(self.0, ScalarCowEnum::Decimal(_))
+    }
+
+    /// Interpret as an exact decimal, if possible
+    ///
+    /// Floats are converted from their shortest representation, so `1.1` is exactly `1.1`.
+    #[cfg(feature = "decimal")]
+    pub fn to_decimal(&self) -> Option<Decimal> {
+        match self.0 {
+            ScalarCowEnum::Integer(ref x) => Some(Decimal::from(*x)),
+            ScalarCowEnum::Float(ref x) if x.is_finite() => parse_decimal(&x.to_string()),
+            ScalarCowEnum::Decimal(ref x) => Some(*x),
+            ScalarCowEnum::Str(ref x)
+            | ScalarCowEnum::SafeStr(ref x)
+            | ScalarCowEnum::TaintedStr(ref x) => parse_decimal(x.as_str()),
             _ => None,
         }
     }
@@ -123,10 +234,16 @@ impl<'s> ScalarCow<'s> {
     }
 
     /// Interpret as a date time, if possible
+    ///
+    /// Numbers are seconds since the Unix epoch.
     pub fn to_date_time(&self) -> Option<DateTime> {
         match self.0 {
             ScalarCowEnum::DateTime(ref x) => Some(*x),
-            ScalarCowEnum::Str(ref x) => DateTime::from_str(x.as_str()),
+            ScalarCowEnum::Integer(ref x) => DateTime::from_unix_timestamp(*x),
+            ScalarCowEnum::Float(ref x) => DateTime::from_unix_timestamp_f64(*x),
+            ScalarCowEnum::Str(ref x)
+            | ScalarCowEnum::SafeStr(ref x)
+            | ScalarCowEnum::TaintedStr(ref x) => DateTime::from_str(x.as_str()),
             _ => None,
         }
     }
@@ -136,15 +253,20 @@ impl<'s> ScalarCow<'s> {
         match self.0 {
             ScalarCowEnum::DateTime(ref x) => Some(x.date()),
             ScalarCowEnum::Date(ref x) => Some(*x),
-            ScalarCowEnum::Str(ref x) => Date::from_str(x.as_str()),
-            _ => None,
+            ScalarCowEnum::Str(ref x)
+            | ScalarCowEnum::SafeStr(ref x)
+            | ScalarCowEnum::TaintedStr(ref x) => Date::from_str(x.as_str())
+                .or_else(|| DateTime::from_str(x.as_str()).map(|d| d.date())),
+            _ => self.to_date_time().map(|d| d.date()),
         }
     }
 
     /// Interpret as a Cow str, borrowing if possible
     pub fn into_cow_str(self) -> Cow<'s, str> {
         match self {
-            Self(ScalarCowEnum::Str(x)) => x.into_cow_str(),
+            Self(ScalarCowEnum::Str(x))
+            | Self(ScalarCowEnum::SafeStr(x))
+            | Self(ScalarCowEnum::TaintedStr(x)) => x.into_cow_str(),
             other => other.into_string().into_cow_str(),
         }
     }
@@ -178,7 +300,7 @@ impl<'s> ValueView for ScalarCow<'s> {
         self.as_view().to_kstr()
     }
     fn to_value(&self) -> Value {
-        self.as_view().to_value()
+        Value::Scalar(self.as_ref().into_owned())
     }
 
     fn as_scalar(&self) -> Option<ScalarCow<'_>> {
@@ -371,6 +493,65 @@ impl<'s> PartialOrd<f64> for ScalarCow<'s> {
 
 impl_copyable!(f32, f64);
 
+#[cfg(feature = "decimal")]
+impl ValueView for Decimal {
+    fn as_debug(&self) -> &dyn fmt::Debug {
+        self
+    }
+
+    fn render(&self) -> DisplayCow<'_> {
+        DisplayCow::Borrowed(self)
+    }
+    fn source(&self) -> DisplayCow<'_> {
+        DisplayCow::Borrowed(self)
+    }
+    fn type_name(&self) -> &'static str {
+        "decimal"
+    }
+    fn query_state(&self, state: State) -> bool {
+        match state {
+            State::Truthy => true,
+            State::DefaultValue => false,
+            State::Empty => false,
+            State::Blank => false,
+        }
+    }
+
+    fn to_kstr(&self) -> KStringCow<'_> {
+        self.render().to_string().into()
+    }
+    fn to_value(&self) -> Value {
+        Value::scalar(*self)
+    }
+
+    fn as_scalar(&self) -> Option<ScalarCow<'_>> {
+        Some(ScalarCow::new(*self))
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl<'s> From<Decimal> for ScalarCow<'s> {
+    fn from(s: Decimal) -> Self {
+        ScalarCow(ScalarCowEnum::Decimal(s))
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl<'s> PartialEq<Decimal> for ScalarCow<'s> {
+    fn eq(&self, other: &Decimal) -> bool {
+        let other = (*other).into();
+        scalar_eq(self, &other)
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl<'s> PartialOrd<Decimal> for ScalarCow<'s> {
+    fn partial_cmp(&self, other: &Decimal) -> Option<Ordering> {
+        let other = (*other).into();
+        scalar_cmp(self, &other)
+    }
+}
+
 impl ValueView for bool {
     fn as_debug(&self) -> &dyn fmt::Debug {
         self
@@ -840,12 +1021,21 @@ fn scalar_eq<'s>(lhs: &ScalarCow<'s>, rhs: &ScalarCow<'s>) -> bool {
         (&ScalarCowEnum::Integer(x), &ScalarCowEnum::Float(y)) => (x as f64) == y,
         (&ScalarCowEnum::Float(x), &ScalarCowEnum::Integer(y)) => x == (y as f64),
         (&ScalarCowEnum::Float(x), &ScalarCowEnum::Float(y)) => x == y,
+        #[cfg(feature = "decimal")]
+        (ScalarCowEnum::Decimal(_), ScalarCowEnum::Integer(_) | ScalarCowEnum::Float(_))
+        | (ScalarCowEnum::Integer(_) | ScalarCowEnum::Float(_), ScalarCowEnum::Decimal(_))
+        | (ScalarCowEnum::Decimal(_), ScalarCowEnum::Decimal(_)) => {
+            decimal_cmp(lhs, rhs) == Some(Ordering::Equal)
+        }
         (&ScalarCowEnum::Bool(x), &ScalarCowEnum::Bool(y)) => x == y,
         (&ScalarCowEnum::DateTime(x), &ScalarCowEnum::DateTime(y)) => x == y,
         (&ScalarCowEnum::Date(x), &ScalarCowEnum::Date(y)) => x == y,
         (&ScalarCowEnum::DateTime(x), &ScalarCowEnum::Date(y)) => x == x.with_date(y),
         (&ScalarCowEnum::Date(x), &ScalarCowEnum::DateTime(y)) => y.with_date(x) == y,
-        (ScalarCowEnum::Str(x), ScalarCowEnum::Str(y)) => x == y,
+        (
+            ScalarCowEnum::Str(x) | ScalarCowEnum::SafeStr(x) | ScalarCowEnum::TaintedStr(x),
+            ScalarCowEnum::Str(y) | ScalarCowEnum::SafeStr(y) | ScalarCowEnum::TaintedStr(y),
+        ) => x == y,
         // encode Ruby truthiness: all values except false and nil are true
         (_, &ScalarCowEnum::Bool(b)) | (&ScalarCowEnum::Bool(b), _) => b,
         _ => false,
@@ -858,16 +1048,41 @@ fn scalar_cmp<'s>(lhs: &ScalarCow<'s>, rhs: &ScalarCow<'s>) -> Option<Ordering>
         (&ScalarCowEnum::Integer(x), &ScalarCowEnum::Float(y)) => (x as f64).partial_cmp(&y),
         (&ScalarCowEnum::Float(x), &ScalarCowEnum::Integer(y)) => x.partial_cmp(&(y as f64)),
         (&ScalarCowEnum::Float(x), &ScalarCowEnum::Float(y)) => x.partial_cmp(&y),
+        #[cfg(feature = "decimal")]
+        (ScalarCowEnum::Decimal(_), ScalarCowEnum::Integer(_) | ScalarCowEnum::Float(_))
+        | (ScalarCowEnum::Integer(_) | ScalarCowEnum::Float(_), ScalarCowEnum::Decimal(_))
+        | (ScalarCowEnum::Decimal(_), ScalarCowEnum::Decimal(_)) => decimal_cmp(lhs, rhs),
         (&ScalarCowEnum::Bool(x), &ScalarCowEnum::Bool(y)) => x.partial_cmp(&y),
         (&ScalarCowEnum::DateTime(x), &ScalarCowEnum::DateTime(y)) => x.partial_cmp(&y),
         (&ScalarCowEnum::Date(x), &ScalarCowEnum::Date(y)) => x.partial_cmp(&y),
         (&ScalarCowEnum::DateTime(x), &ScalarCowEnum::Date(y)) => x.partial_cmp(&x.with_date(y)),
         (&ScalarCowEnum::Date(x), &ScalarCowEnum::DateTime(y)) => y.with_date(x).partial_cmp(&y),
-        (ScalarCowEnum::Str(x), ScalarCowEnum::Str(y)) => x.partial_cmp(y),
+        (
+            ScalarCowEnum::Str(x) | ScalarCowEnum::SafeStr(x) | ScalarCowEnum::TaintedStr(x),
+            ScalarCowEnum::Str(y) | ScalarCowEnum::SafeStr(y) | ScalarCowEnum::TaintedStr(y),
+        ) => x.partial_cmp(y),
         _ => None,
     }
 }
 
+/// Compare exactly, falling back to floats for numbers beyond a `Decimal`'s range.
+#[cfg(feature = "decimal")]
+fn decimal_cmp<'s>(lhs: &ScalarCow<'s>, rhs: &ScalarCow<'s>) -> Option<Ordering> {
+    match (lhs.to_decimal(), rhs.to_decimal()) {
+        (Some(x), Some(y)) => x.partial_cmp(&y),
+        _ => lhs.to_float()?.partial_cmp(&rhs.to_float()?),
+    }
+}
+
+#[cfg(feature = "decimal")]
+fn parse_decimal(s: &str) -> Option<Decimal> {
+    use std::str::FromStr;
+
+    Decimal::from_str(s)
+        .or_else(|_| Decimal::from_scientific(s))
+        .ok()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1110,4 +1325,25 @@ mod test {
             assert_eq!(is_borrowed(extract_cow_str(&sc)), true);
         }
     }
+
+    #[test]
+    #[cfg(feature = "decimal")]
+    fn test_decimal() {
+        let price = ScalarCow::new("19.90".parse::<Decimal>().unwrap());
+        assert_eq!(price.render().to_string(), "19.90");
+        assert_eq!(price.type_name(), "decimal");
+        assert_eq!(price, 19.9f64);
+        assert!(price > ScalarCow::new(19i64));
+        assert_eq!(ScalarCow::new(1.1f64).to_decimal(), "1.1".parse().ok());
+        assert_eq!(ScalarCow::new(20i64), "20.00".parse::<Decimal>().unwrap());
+    }
+
+    #[test]
+    fn test_taint_overrides_safe() {
+        let html = ScalarCow::safe("<b>hi</b>");
+        assert!(html.is_safe());
+        let html = html.into_tainted();
+        assert!(html.is_tainted());
+        assert!(!html.is_safe());
+    }
 }
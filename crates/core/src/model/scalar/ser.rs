@@ -21,6 +21,14 @@ where
     value.serialize(ScalarSerializer).map_err(|e| e.into())
 }
 
+#[cfg(feature = "decimal")]
+pub(crate) fn serialize_decimal<S>(value: &super::Decimal, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.collect_str(value)
+}
+
 pub(crate) struct ScalarSerializer;
 
 fn scalar_must_be_a_string() -> SerError {
@@ -0,0 +1,162 @@
+use std::fmt;
+
+use super::DateTime;
+
+/// A time zone to display dates in.
+///
+/// Fixed offsets are always available.  With the `tz` feature, IANA time zones like
+/// `America/New_York` are supported from a bundled copy of the tz database, following their
+/// daylight saving time transitions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeZone {
+    inner: TimeZoneImpl,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimeZoneImpl {
+    Fixed(time::UtcOffset),
+    #[cfg(feature = "tz")]
+    Named(&'static time_tz::Tz),
+}
+
+impl TimeZone {
+    /// Coordinated Universal Time.
+    pub const UTC: Self = Self {
+        inner: TimeZoneImpl::Fixed(time::UtcOffset::UTC),
+    };
+
+    /// A time zone that is always `offset` from UTC.
+    pub fn fixed(offset: time::UtcOffset) -> Self {
+        Self {
+            inner: TimeZoneImpl::Fixed(offset),
+        }
+    }
+
+    /// Look up a time zone by name.
+    ///
+    /// Accepts `UTC`, fixed offsets like `+05:30` or `-0800` and, with the `tz` feature, IANA
+    /// names like `Europe/Paris`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("utc") || name == "Z" {
+            return Some(Self::UTC);
+        }
+        if let Some(offset) = parse_offset(name) {
+            return Some(Self::fixed(offset));
+        }
+        Self::from_tz_name(name)
+    }
+
+    #[cfg(feature = "tz")]
+    fn from_tz_name(name: &str) -> Option<Self> {
+        time_tz::timezones::get_by_name(name).map(|tz| Self {
+            inner: TimeZoneImpl::Named(tz),
+        })
+    }
+
+    #[cfg(not(feature = "tz"))]
+    fn from_tz_name(_name: &str) -> Option<Self> {
+        None
+    }
+
+    /// The offset from UTC in effect at `date_time`.
+    #[cfg_attr(not(feature = "tz"), allow(unused_variables))]
+    pub fn offset_at(&self, date_time: DateTime) -> time::UtcOffset {
+        match self.inner {
+            TimeZoneImpl::Fixed(offset) => offset,
+            #[cfg(feature = "tz")]
+            TimeZoneImpl::Named(tz) => {
+                use time_tz::{Offset, TimeZone};
+                tz.get_offset_utc(&date_time.inner).to_utc()
+            }
+        }
+    }
+}
+
+impl Default for TimeZone {
+    fn default() -> Self {
+        Self::UTC
+    }
+}
+
+impl fmt::Display for TimeZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.inner {
+            TimeZoneImpl::Fixed(offset) if offset.is_utc() => write!(f, "UTC"),
+            TimeZoneImpl::Fixed(offset) => {
+                let (hours, minutes, _) = offset.as_hms();
+                let sign = if offset.is_negative() { '-' } else { '+' };
+                write!(f, "{}{:02}:{:02}", sign, hours.abs(), minutes.abs())
+            }
+            #[cfg(feature = "tz")]
+            TimeZoneImpl::Named(tz) => write!(f, "{}", time_tz::TimeZone::name(tz)),
+        }
+    }
+}
+
+/// Parse `+HH:MM`, `+HHMM` or `+HH`.
+fn parse_offset(name: &str) -> Option<time::UtcOffset> {
+    let (sign, rest) = match name.as_bytes().first()? {
+        b'+' => (1, &name[1..]),
+        b'-' => (-1, &name[1..]),
+        _ => return None,
+    };
+    let rest = rest.replace(':', "");
+    if !rest.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match rest.len() {
+        2 => (rest.parse::<i8>().ok()?, 0),
+        4 => (rest[..2].parse::<i8>().ok()?, rest[2..].parse::<i8>().ok()?),
+        _ => return None,
+    };
+    time::UtcOffset::from_hms(sign * hours, sign * minutes, 0).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fixed() {
+        let tz = TimeZone::from_name("+05:30").unwrap();
+        assert_eq!(tz.to_string(), "+05:30");
+        assert_eq!(TimeZone::from_name("-0800").unwrap().to_string(), "-08:00");
+        assert_eq!(TimeZone::from_name("utc"), Some(TimeZone::UTC));
+        assert_eq!(TimeZone::from_name("Hello"), None);
+
+        let date = DateTime::from_str("2016-06-13 12:00:00 +0000").unwrap();
+        assert_eq!(
+            date.with_timezone(tz).to_string(),
+            "2016-06-13 17:30:00 +0530"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "tz")]
+    fn test_named_dst() {
+        let tz = TimeZone::from_name("America/New_York").unwrap();
+        assert_eq!(tz.to_string(), "America/New_York");
+
+        let winter = DateTime::from_str("2024-01-15 12:00:00 +0000").unwrap();
+        assert_eq!(
+            winter.with_timezone(tz).to_string(),
+            "2024-01-15 07:00:00 -0500"
+        );
+        let summer = DateTime::from_str("2024-07-15 12:00:00 +0000").unwrap();
+        assert_eq!(
+            summer.with_timezone(tz).to_string(),
+            "2024-07-15 08:00:00 -0400"
+        );
+        // Just before and after the spring-forward transition.
+        let before = DateTime::from_str("2024-03-10 06:59:00 +0000").unwrap();
+        assert_eq!(
+            before.with_timezone(tz).to_string(),
+            "2024-03-10 01:59:00 -0500"
+        );
+        let after = DateTime::from_str("2024-03-10 07:00:00 +0000").unwrap();
+        assert_eq!(
+            after.with_timezone(tz).to_string(),
+            "2024-03-10 03:00:00 -0400"
+        );
+    }
+}
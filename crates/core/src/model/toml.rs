@@ -0,0 +1,65 @@
+//! Render `toml` values without converting them to `Value`.
+
+use crate::model::{Date, DateTime, ScalarCow};
+
+use super::external::{external_object_view, external_value_view, ExternalValue};
+
+fn classify(value: &::toml::Value) -> ExternalValue<'_> {
+    match value {
+        ::toml::Value::String(s) => ExternalValue::Str(s),
+        ::toml::Value::Integer(i) => ExternalValue::Scalar(ScalarCow::new(*i)),
+        ::toml::Value::Float(f) => ExternalValue::Scalar(ScalarCow::new(*f)),
+        ::toml::Value::Boolean(b) => ExternalValue::Scalar(ScalarCow::new(*b)),
+        ::toml::Value::Datetime(d) => ExternalValue::Scalar(datetime_scalar(d)),
+        ::toml::Value::Array(a) => ExternalValue::Array(a),
+        ::toml::Value::Table(t) => ExternalValue::Object(t),
+    }
+}
+
+/// Dates and date-times become Liquid's; local times, which Liquid has no type for, stay strings.
+fn datetime_scalar(datetime: &::toml::value::Datetime) -> ScalarCow<'static> {
+    let s = datetime.to_string();
+    match (datetime.date, datetime.time) {
+        (Some(_), Some(_)) => DateTime::from_str(&s).map(ScalarCow::new),
+        (Some(_), None) => Date::from_str(&s).map(ScalarCow::new),
+        _ => None,
+    }
+    .unwrap_or_else(|| ScalarCow::new(s))
+}
+
+external_value_view!(::toml::Value, classify);
+external_object_view!(::toml::Table, keyed_by_string);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::ObjectView;
+
+    #[test]
+    fn test_toml() {
+        let table: ::toml::Table = ::toml::from_str(
+            r#"
+name = "Liquid"
+stars = 5
+published = 2016-02-16T10:00:00Z
+released = 2016-02-16
+tags = ["a", "b"]
+"#,
+        )
+        .unwrap();
+        let view: &dyn ObjectView = &table;
+        assert_eq!(view.get("name").unwrap().to_kstr(), "Liquid");
+        assert_eq!(view.get("stars").unwrap().as_scalar().unwrap(), 5i64);
+        assert_eq!(
+            view.get("published")
+                .unwrap()
+                .as_scalar()
+                .unwrap()
+                .to_date_time(),
+            DateTime::from_str("2016-02-16 10:00:00 +0000")
+        );
+        assert_eq!(view.get("released").unwrap().type_name(), "date");
+        assert_eq!(view.get("tags").unwrap().as_array().unwrap().size(), 2);
+    }
+}
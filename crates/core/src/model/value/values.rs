@@ -51,6 +51,18 @@ impl Value {
         }
     }
 
+    /// Mark all strings within as coming from an untrusted source.
+    pub fn into_tainted(self) -> Self {
+        match self {
+            Value::Scalar(x) => Value::Scalar(x.into_tainted()),
+            Value::Array(x) => Value::Array(x.into_iter().map(Value::into_tainted).collect()),
+            Value::Object(x) => {
+                Value::Object(x.into_iter().map(|(k, v)| (k, v.into_tainted())).collect())
+            }
+            other => other,
+        }
+    }
+
     /// Extracts the scalar value if it is a scalar.
     pub fn into_scalar(self) -> Option<Scalar> {
         match self {
@@ -0,0 +1,89 @@
+//! Render `serde_yaml` values without converting them to `Value`.
+
+use crate::model::{KStringCow, ObjectView, ScalarCow, ValueView};
+
+use super::external::{external_object_view, external_value_view, ExternalValue};
+
+fn classify(value: &serde_yaml::Value) -> ExternalValue<'_> {
+    match value {
+        serde_yaml::Value::Null => ExternalValue::Nil,
+        serde_yaml::Value::Bool(b) => ExternalValue::Scalar(ScalarCow::new(*b)),
+        serde_yaml::Value::Number(n) => ExternalValue::Scalar(
+            n.as_i64()
+                .map(ScalarCow::new)
+                .or_else(|| n.as_f64().map(ScalarCow::new))
+                .expect("numbers are i64, u64 or f64"),
+        ),
+        serde_yaml::Value::String(s) => ExternalValue::Str(s),
+        serde_yaml::Value::Sequence(s) => ExternalValue::Array(s),
+        serde_yaml::Value::Mapping(m) => ExternalValue::Object(m),
+    }
+}
+
+external_value_view!(serde_yaml::Value, classify);
+
+/// Mapping keys can be any YAML value; non-strings are looked up by how they render.
+fn key_str(key: &serde_yaml::Value) -> KStringCow<'_> {
+    match key {
+        serde_yaml::Value::String(s) => KStringCow::from_ref(s),
+        _ => key.to_kstr(),
+    }
+}
+
+external_object_view!(serde_yaml::Mapping);
+
+impl ObjectView for serde_yaml::Mapping {
+    fn as_value(&self) -> &dyn ValueView {
+        self
+    }
+
+    fn size(&self) -> i64 {
+        self.len() as i64
+    }
+
+    fn keys<'k>(&'k self) -> Box<dyn Iterator<Item = KStringCow<'k>> + 'k> {
+        Box::new(self.iter().map(|(k, _)| key_str(k)))
+    }
+
+    fn values<'k>(&'k self) -> Box<dyn Iterator<Item = &'k dyn ValueView> + 'k> {
+        Box::new(self.iter().map(|(_, v)| v as &dyn ValueView))
+    }
+
+    fn iter<'k>(&'k self) -> Box<dyn Iterator<Item = (KStringCow<'k>, &'k dyn ValueView)> + 'k> {
+        Box::new(self.iter().map(|(k, v)| (key_str(k), v as &dyn ValueView)))
+    }
+
+    fn contains_key(&self, index: &str) -> bool {
+        ObjectView::get(self, index).is_some()
+    }
+
+    fn get<'s>(&'s self, index: &str) -> Option<&'s dyn ValueView> {
+        let value = self
+            .get(&serde_yaml::Value::String(index.to_owned()))
+            .or_else(|| {
+                self.iter()
+                    .find(|(k, _)| !k.is_string() && key_str(k) == index)
+                    .map(|(_, v)| v)
+            });
+        value.map(|v| v as &dyn ValueView)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_yaml() {
+        let yaml: serde_yaml::Value =
+            serde_yaml::from_str("name: Liquid\nstars: 5\n2016: leap\ntags: [a, b]\nnone: ~\n")
+                .unwrap();
+        let view: &dyn ObjectView = yaml.as_mapping().unwrap();
+        assert_eq!(view.get("name").unwrap().to_kstr(), "Liquid");
+        assert_eq!(view.get("stars").unwrap().as_scalar().unwrap(), 5i64);
+        assert_eq!(view.get("2016").unwrap().to_kstr(), "leap");
+        assert_eq!(view.get("tags").unwrap().as_array().unwrap().size(), 2);
+        assert!(view.get("none").unwrap().is_nil());
+        assert!(!view.contains_key("missing"));
+    }
+}
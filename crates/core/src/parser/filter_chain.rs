@@ -3,46 +3,142 @@ use std::io::Write;
 
 use super::Filter;
 use crate::error::{Result, ResultLiquidExt, ResultLiquidReplaceExt};
-use crate::model::{ValueCow, ValueView};
+use crate::model::{escape_html, KString, Value, ValueCow, ValueView};
+use crate::runtime::Analyzer;
 use crate::runtime::Expression;
 use crate::runtime::Renderable;
 use crate::runtime::Runtime;
+use crate::runtime::{is_tainted, report_tainted, TaintMode, TaintTracker};
 
 /// A `Value` expression.
 #[derive(Debug)]
 pub struct FilterChain {
     entry: Expression,
     filters: Vec<Box<dyn Filter>>,
+    calls: Vec<FilterCall>,
+    escape: bool,
 }
 
 impl FilterChain {
     /// Create a new expression.
+    ///
+    /// `analyze` reports each filter under the name it displays with, like `append : "x"`;
+    /// use `with_calls` to also report the variables its arguments use.
     pub fn new(entry: Expression, filters: Vec<Box<dyn Filter>>) -> Self {
-        Self { entry, filters }
+        let calls = filters
+            .iter()
+            .map(|filter| {
+                let display = filter.to_string();
+                let name = display.split(':').next().unwrap_or_default().trim();
+                FilterCall::new(name, Vec::new())
+            })
+            .collect();
+        Self {
+            entry,
+            filters,
+            calls,
+            escape: false,
+        }
+    }
+
+    /// Remember how the filters were called, for `analyze`.
+    pub fn with_calls(mut self, calls: Vec<FilterCall>) -> Self {
+        self.calls = calls;
+        self
+    }
+
+    /// HTML-escape the rendered value, unless it is a safe string.
+    pub(crate) fn with_escape(mut self, escape: bool) -> Self {
+        self.escape = escape;
+        self
     }
 
     /// Process `Value` expression within `runtime`'s stack.
     pub fn evaluate<'s>(&'s self, runtime: &'s dyn Runtime) -> Result<ValueCow<'s>> {
+        let (entry, tainted) = self.evaluate_tainted(runtime)?;
+        let entry = match entry.as_scalar() {
+            // Keep derived strings tainted, like for `assign`
+            Some(scalar) if tainted.is_some() && !scalar.is_tainted() => {
+                ValueCow::Owned(Value::Scalar(scalar.into_owned().into_tainted()))
+            }
+            _ => entry,
+        };
+        Ok(entry)
+    }
+
+    /// Process `Value` expression, reporting the tainted variable it was derived from, if any.
+    ///
+    /// Taint is only tracked when checking for it.
+    fn evaluate_tainted<'s>(
+        &'s self,
+        runtime: &'s dyn Runtime,
+    ) -> Result<(ValueCow<'s>, Option<String>)> {
         // take either the provided value or the value from the provided variable
         let mut entry = self.entry.evaluate(runtime)?;
 
-        // apply all specified filters
+        if runtime.taint_mode() == TaintMode::Lax {
+            // apply all specified filters
+            for filter in &self.filters {
+                entry = ValueCow::Owned(apply_filter(filter.as_ref(), entry, runtime)?);
+            }
+            return Ok((entry, None));
+        }
+
+        // Filters evaluate their own arguments, so note the variables they read
+        let tracker = TaintTracker::new(runtime);
+        let mut sources = Vec::new();
+        let mut from_entry = true;
         for filter in &self.filters {
-            entry = ValueCow::Owned(
-                filter
-                    .evaluate(entry.as_view(), runtime)
-                    .trace("Filter error")
-                    .context_key("filter")
-                    .value_with(|| format!("{}", filter).into())
-                    .context_key("input")
-                    .value_with(|| format!("{}", entry.source()).into())?,
-            );
+            entry = ValueCow::Owned(apply_filter(filter.as_ref(), entry, &tracker)?);
+            sources.extend(tracker.take());
+
+            if !carries_markup(entry.as_view()) {
+                from_entry = false;
+                sources.clear();
+            }
         }
 
-        Ok(entry)
+        if !carries_markup(entry.as_view()) {
+            return Ok((entry, None));
+        }
+        // Each source is checked once, now that the output could be tainted
+        let tainted = if from_entry && is_tainted(self.entry.evaluate(runtime)?.as_view()) {
+            Some(self.entry.to_string())
+        } else {
+            sources
+                .iter()
+                .find(|path| {
+                    runtime
+                        .try_get(path)
+                        .map(|value| is_tainted(value.as_view()))
+                        .unwrap_or(false)
+                })
+                .map(|path| path.to_string())
+        };
+        Ok((entry, tainted))
     }
 }
 
+/// Whether `value` could carry markup from a tainted value into the output.
+///
+/// Safe strings were escaped, and numbers and the like carry no markup.
+fn carries_markup(value: &dyn ValueView) -> bool {
+    match value.as_scalar() {
+        Some(scalar) => scalar.is_str() && !scalar.is_safe(),
+        None => value.as_array().is_some() || value.as_object().is_some(),
+    }
+}
+
+fn apply_filter(filter: &dyn Filter, entry: ValueCow<'_>, runtime: &dyn Runtime) -> Result<Value> {
+    filter
+        .evaluate(entry.as_view(), runtime)
+        .trace("Filter error")
+        .context_key("filter")
+        .value_with(|| format!("{}", filter).into())
+        .context_key("input")
+        .value_with(|| format!("{}", entry.source()).into())
+}
+
 impl fmt::Display for FilterChain {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
@@ -56,8 +152,53 @@ impl fmt::Display for FilterChain {
 
 impl Renderable for FilterChain {
     fn render_to(&self, writer: &mut dyn Write, runtime: &dyn Runtime) -> Result<()> {
-        let entry = self.evaluate(runtime)?;
-        write!(writer, "{}", entry.render()).replace("Failed to render")?;
-        Ok(())
+        let (entry, tainted) = self.evaluate_tainted(runtime)?;
+        if let Some(source) = tainted.filter(|_| !self.escape) {
+            report_tainted(runtime, &source)?;
+        }
+
+        write_value(writer, entry.as_view(), self.escape)
+    }
+
+    fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        self.entry.analyze(analyzer);
+        for call in &self.calls {
+            analyzer.filter(&call.name);
+            for arg in &call.arguments {
+                arg.analyze(analyzer);
+            }
+        }
+    }
+}
+
+/// Write `value` the way `{{ }}` does, for tags that output values.
+///
+/// With `escape`, which tags should take from `Language::auto_escape`, the value is HTML-escaped
+/// unless it is a safe string.
+pub fn write_value(writer: &mut dyn Write, value: &dyn ValueView, escape: bool) -> Result<()> {
+    let is_safe = value.as_scalar().map(|s| s.is_safe()).unwrap_or(false);
+    if escape && !is_safe {
+        let rendered = value.render().to_string();
+        write!(writer, "{}", escape_html(&rendered)).replace("Failed to render")?;
+    } else {
+        write!(writer, "{}", value.render()).replace("Failed to render")?;
+    }
+    Ok(())
+}
+
+/// The name and arguments a filter was parsed from.
+#[derive(Debug)]
+pub struct FilterCall {
+    name: KString,
+    arguments: Vec<Expression>,
+}
+
+impl FilterCall {
+    /// Describe a call of the filter `name` with `arguments`, positional then keyword.
+    pub fn new(name: &str, arguments: Vec<Expression>) -> Self {
+        Self {
+            name: KString::from_ref(name),
+            arguments,
+        }
     }
 }
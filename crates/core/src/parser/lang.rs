@@ -2,6 +2,7 @@ use super::ParseBlock;
 use super::ParseFilter;
 use super::ParseTag;
 use super::PluginRegistry;
+use super::SandboxPolicy;
 
 #[derive(Clone, Default)]
 #[non_exhaustive]
@@ -9,6 +10,10 @@ pub struct Language {
     pub blocks: PluginRegistry<Box<dyn ParseBlock>>,
     pub tags: PluginRegistry<Box<dyn ParseTag>>,
     pub filters: PluginRegistry<Box<dyn ParseFilter>>,
+    /// HTML-escape the output of every `{{ }}`, except for safe strings.
+    pub auto_escape: bool,
+    /// Restrict what templates may use.
+    pub sandbox: Option<SandboxPolicy>,
 }
 
 impl Language {
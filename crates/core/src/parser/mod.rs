@@ -4,6 +4,7 @@ mod filter_chain;
 mod lang;
 mod parser;
 mod registry;
+mod sandbox;
 mod tag;
 mod text;
 
@@ -13,6 +14,7 @@ pub use filter_chain::*;
 pub use lang::*;
 pub use parser::*;
 pub use registry::*;
+pub use sandbox::*;
 pub use tag::*;
 
 use text::Text;
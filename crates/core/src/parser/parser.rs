@@ -3,15 +3,19 @@
 //! This module contains functions than can be used for writing plugins
 //! but should be ignored for simple usage.
 
+use std::fmt;
+use std::io::Write;
+
 use crate::error::{Error, Result, ResultLiquidExt};
-use crate::model::Value;
+use crate::model::{KString, Value};
 use crate::runtime::Expression;
 use crate::runtime::Renderable;
 use crate::runtime::Variable;
+use crate::runtime::{Analyzer, Runtime};
 
 use super::Language;
 use super::Text;
-use super::{Filter, FilterArguments, FilterChain};
+use super::{Filter, FilterArguments, FilterCall, FilterChain};
 
 use pest::Parser;
 
@@ -56,6 +60,10 @@ fn error_from_pair(pair: Pair, msg: String) -> Error {
 
 /// Parses the provided &str into a number of Renderable items.
 pub fn parse(text: &str, options: &Language) -> Result<Vec<Box<dyn Renderable>>> {
+    if let Some(sandbox) = &options.sandbox {
+        sandbox.check_source(text)?;
+    }
+
     let mut liquid = LiquidParser::parse(Rule::LaxLiquidFile, text)
         .expect("Parsing with Rule::LaxLiquidFile should not raise errors, but InvalidLiquid tokens instead.")
         .next()
@@ -180,7 +188,7 @@ fn parse_value(value: Pair) -> Expression {
 
 /// Parses a `FilterCall` from a `Pair` with a filter.
 /// This `Pair` must be `Rule::Filter`.
-fn parse_filter(filter: Pair, options: &Language) -> Result<Box<dyn Filter>> {
+fn parse_filter(filter: Pair, options: &Language) -> Result<(Box<dyn Filter>, FilterCall)> {
     if filter.as_rule() != Rule::Filter {
         panic!("Expected a filter.");
     }
@@ -210,11 +218,23 @@ fn parse_filter(filter: Pair, options: &Language) -> Result<Box<dyn Filter>> {
         }
     }
 
+    let call = FilterCall::new(
+        name,
+        positional_args
+            .iter()
+            .chain(keyword_args.iter().map(|(_, v)| v))
+            .cloned()
+            .collect(),
+    );
+
     let args = FilterArguments {
         positional: Box::new(positional_args.into_iter()),
         keyword: Box::new(keyword_args.into_iter()),
     };
 
+    if let Some(sandbox) = &options.sandbox {
+        sandbox.check_filter(name)?;
+    }
     let f = options.filters.get(name).ok_or_else(|| {
         let mut available: Vec<_> = options.filters.plugin_names().collect();
         available.sort_unstable();
@@ -230,7 +250,7 @@ fn parse_filter(filter: Pair, options: &Language) -> Result<Box<dyn Filter>> {
         .context_key("filter")
         .value_with(|| filter_str.to_string().into())?;
 
-    Ok(f)
+    Ok((f, call))
 }
 
 /// Parses a `FilterChain` from a `Pair` with a filter chain.
@@ -247,9 +267,9 @@ fn parse_filter_chain(chain: Pair, options: &Language) -> Result<FilterChain> {
             .expect("A filterchain always has starts by a value."),
     );
     let filters: Result<Vec<_>> = chain.map(|f| parse_filter(f, options)).collect();
-    let filters = filters?;
+    let (filters, calls) = filters?.into_iter().unzip();
 
-    let filters = FilterChain::new(entry, filters);
+    let filters = FilterChain::new(entry, filters).with_calls(calls);
     Ok(filters)
 }
 
@@ -259,6 +279,8 @@ pub struct TagBlock<'a: 'b, 'b> {
     end_tag: &'b str,
     iter: &'b mut dyn Iterator<Item = Pair<'a>>,
     closed: bool,
+    top_level: bool,
+    end_with_template: bool,
 }
 
 impl<'a, 'b> TagBlock<'a, 'b> {
@@ -266,15 +288,34 @@ impl<'a, 'b> TagBlock<'a, 'b> {
         start_tag: &'b str,
         end_tag: &'b str,
         next_elements: &'b mut dyn Iterator<Item = Pair<'a>>,
+        top_level: bool,
     ) -> Self {
         TagBlock {
             start_tag,
             end_tag,
             iter: next_elements,
             closed: false,
+            top_level,
+            end_with_template: false,
         }
     }
 
+    /// Close the block at the end of the template, rather than requiring its end-tag.
+    ///
+    /// This is for blocks that take over the rest of the template, like `extends`, so it is an
+    /// error unless the block is at the top-level of the template.
+    pub fn end_with_template(&mut self) -> Result<()> {
+        if !self.top_level {
+            return Error::with_msg(format!(
+                "{{% {} %}} must not be nested within another block",
+                self.start_tag
+            ))
+            .into_err();
+        }
+        self.end_with_template = true;
+        Ok(())
+    }
+
     /// Returns the next element of the block, if any, similarly to an iterator.
     ///
     /// However, if the input text reaches its end and the block is not closed,
@@ -288,6 +329,10 @@ impl<'a, 'b> TagBlock<'a, 'b> {
         let element = self.iter.next().expect("File shouldn't end before EOI.");
 
         if element.as_rule() == Rule::EOI {
+            if self.end_with_template {
+                self.closed = true;
+                return Ok(None);
+            }
             return error_from_pair(
                 element,
                 format!("Unclosed block. {{% {} %}} tag expected.", self.end_tag),
@@ -541,7 +586,7 @@ impl<'a> Tag<'a> {
         tag_block: &mut TagBlock,
         options: &Language,
     ) -> Result<Box<dyn Renderable>> {
-        self.parse_pair(&mut tag_block.iter, options)
+        self.parse_pair(&mut tag_block.iter, options, false)
     }
 
     /// The same as `parse`, but directly takes an iterator over `Pair`s instead of a TagBlock.
@@ -549,18 +594,32 @@ impl<'a> Tag<'a> {
         self,
         next_elements: &mut dyn Iterator<Item = Pair>,
         options: &Language,
+        top_level: bool,
     ) -> Result<Box<dyn Renderable>> {
         let (name, tokens) = (self.name, self.tokens);
         let position = name.as_span();
         let name = name.as_str();
 
         if let Some(plugin) = options.tags.get(name) {
-            plugin.parse(tokens, options)
+            if let Some(sandbox) = &options.sandbox {
+                sandbox.check_tag(name)?;
+            }
+            plugin
+                .parse(tokens, options)
+                .map(|renderable| TagNode::boxed(name, renderable))
         } else if let Some(plugin) = options.blocks.get(name) {
+            if let Some(sandbox) = &options.sandbox {
+                sandbox.check_block(name)?;
+            }
             let reflection = plugin.reflection();
-            let block = TagBlock::new(reflection.start_tag(), reflection.end_tag(), next_elements);
-            let renderables = plugin.parse(tokens, block, options)?;
-            Ok(renderables)
+            let block = TagBlock::new(
+                reflection.start_tag(),
+                reflection.end_tag(),
+                next_elements,
+                top_level,
+            );
+            let renderable = plugin.parse(tokens, block, options)?;
+            Ok(TagNode::boxed(name, renderable))
         } else {
             let pest_error = ::pest::error::Error::new_from_span(
                 ::pest::error::ErrorVariant::CustomError {
@@ -583,6 +642,42 @@ impl<'a> Tag<'a> {
     }
 }
 
+/// A parsed tag or block, remembering the name it was registered under for `analyze`.
+struct TagNode {
+    name: KString,
+    renderable: Box<dyn Renderable>,
+}
+
+impl TagNode {
+    fn boxed(name: &str, renderable: Box<dyn Renderable>) -> Box<dyn Renderable> {
+        Box::new(Self {
+            name: KString::from_ref(name),
+            renderable,
+        })
+    }
+}
+
+impl fmt::Debug for TagNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.renderable.fmt(f)
+    }
+}
+
+impl Renderable for TagNode {
+    fn render(&self, runtime: &dyn Runtime) -> Result<String> {
+        self.renderable.render(runtime)
+    }
+
+    fn render_to(&self, writer: &mut dyn Write, runtime: &dyn Runtime) -> Result<()> {
+        self.renderable.render_to(writer, runtime)
+    }
+
+    fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        analyzer.tag(&self.name);
+        self.renderable.analyze(analyzer);
+    }
+}
+
 /// An element that is an expression.
 pub struct Exp<'a> {
     element: Pair<'a>,
@@ -609,7 +704,8 @@ impl<'a> Exp<'a> {
             .next()
             .expect("An expression consists of one filterchain.");
 
-        let filter_chain = parse_filter_chain(filter_chain, options)?;
+        let filter_chain =
+            parse_filter_chain(filter_chain, options)?.with_escape(options.auto_escape);
         Ok(Box::new(filter_chain))
     }
 
@@ -735,7 +831,7 @@ impl<'a> BlockElement<'a> {
     ) -> Result<Box<dyn Renderable>> {
         match self {
             BlockElement::Raw(raw) => Ok(raw.into_renderable()),
-            BlockElement::Tag(tag) => tag.parse_pair(next_elements, options),
+            BlockElement::Tag(tag) => tag.parse_pair(next_elements, options, true),
             BlockElement::Expression(exp) => exp.parse(options),
             BlockElement::Invalid(invalid) => invalid.parse_pair(next_elements),
         }
@@ -0,0 +1,135 @@
+use std::collections::BTreeSet;
+
+use crate::error::{Error, Result};
+
+/// Restrictions for parsing templates from untrusted authors.
+///
+/// Tags, blocks and filters are checked by name while parsing, so a template that uses a
+/// forbidden one fails to parse.
+#[derive(Clone, Debug, Default)]
+pub struct SandboxPolicy {
+    tags: Access,
+    blocks: Access,
+    filters: Access,
+    max_partials: Option<usize>,
+    max_source_len: Option<usize>,
+}
+
+impl SandboxPolicy {
+    /// Create a policy that allows everything.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Only allow these tags (and no others).
+    pub fn allow_tags<I: IntoIterator<Item = S>, S: Into<String>>(mut self, names: I) -> Self {
+        self.tags.allow(names);
+        self
+    }
+
+    /// Forbid these tags.
+    pub fn deny_tags<I: IntoIterator<Item = S>, S: Into<String>>(mut self, names: I) -> Self {
+        self.tags.deny(names);
+        self
+    }
+
+    /// Only allow these blocks (and no others).
+    pub fn allow_blocks<I: IntoIterator<Item = S>, S: Into<String>>(mut self, names: I) -> Self {
+        self.blocks.allow(names);
+        self
+    }
+
+    /// Forbid these blocks.
+    pub fn deny_blocks<I: IntoIterator<Item = S>, S: Into<String>>(mut self, names: I) -> Self {
+        self.blocks.deny(names);
+        self
+    }
+
+    /// Only allow these filters (and no others).
+    pub fn allow_filters<I: IntoIterator<Item = S>, S: Into<String>>(mut self, names: I) -> Self {
+        self.filters.allow(names);
+        self
+    }
+
+    /// Forbid these filters.
+    pub fn deny_filters<I: IntoIterator<Item = S>, S: Into<String>>(mut self, names: I) -> Self {
+        self.filters.deny(names);
+        self
+    }
+
+    /// Limit how many times partial-templates may be accessed in one render.
+    pub fn max_partials(mut self, max: usize) -> Self {
+        self.max_partials = Some(max);
+        self
+    }
+
+    /// Limit the size, in bytes, of the template source.
+    pub fn max_source_len(mut self, max: usize) -> Self {
+        self.max_source_len = Some(max);
+        self
+    }
+
+    /// How many times partial-templates may be accessed in one render.
+    pub fn partial_limit(&self) -> Option<usize> {
+        self.max_partials
+    }
+
+    pub(crate) fn check_source(&self, text: &str) -> Result<()> {
+        match self.max_source_len {
+            Some(max) if max < text.len() => Error::with_msg("Template exceeds the sandbox policy")
+                .context("size", text.len().to_string())
+                .context("limit", max.to_string())
+                .into_err(),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn check_tag(&self, name: &str) -> Result<()> {
+        check(&self.tags, "Tag", name)
+    }
+
+    pub(crate) fn check_block(&self, name: &str) -> Result<()> {
+        check(&self.blocks, "Block", name)
+    }
+
+    pub(crate) fn check_filter(&self, name: &str) -> Result<()> {
+        check(&self.filters, "Filter", name)
+    }
+}
+
+fn check(access: &Access, kind: &str, name: &str) -> Result<()> {
+    if access.is_allowed(name) {
+        Ok(())
+    } else {
+        Error::with_msg(format!("{} is not allowed by the sandbox policy", kind))
+            .context("requested", name.to_owned())
+            .into_err()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct Access {
+    allowed: Option<BTreeSet<String>>,
+    denied: BTreeSet<String>,
+}
+
+impl Access {
+    fn allow<I: IntoIterator<Item = S>, S: Into<String>>(&mut self, names: I) {
+        self.allowed
+            .get_or_insert_with(Default::default)
+            .extend(names.into_iter().map(Into::into));
+    }
+
+    fn deny<I: IntoIterator<Item = S>, S: Into<String>>(&mut self, names: I) {
+        self.denied.extend(names.into_iter().map(Into::into));
+    }
+
+    fn is_allowed(&self, name: &str) -> bool {
+        let allowed = self
+            .allowed
+            .as_ref()
+            .map(|allowed| allowed.contains(name))
+            .unwrap_or(true);
+        allowed && !self.denied.contains(name)
+    }
+}
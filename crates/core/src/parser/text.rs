@@ -1,6 +1,7 @@
 use std::io::Write;
 
 use crate::error::{Result, ResultLiquidReplaceExt};
+use crate::runtime::Analyzer;
 use crate::runtime::Renderable;
 use crate::runtime::Runtime;
 
@@ -22,4 +23,6 @@ impl Renderable for Text {
         write!(writer, "{}", &self.text).replace("Failed to render")?;
         Ok(())
     }
+
+    fn analyze(&self, _analyzer: &mut Analyzer<'_>) {}
 }
@@ -0,0 +1,355 @@
+use std::borrow;
+use std::collections::BTreeMap;
+use std::io;
+use std::str;
+
+use crate::error::Result;
+use crate::error::{Error, ResultLiquidReplaceExt};
+
+use super::PartialSource;
+
+const HEADER: &str = "liquid-bundle 1";
+
+/// Write partial-templates into a single bundle, read back by `BundleSource`.
+///
+/// A bundle is UTF-8 text:
+///
+/// ```text
+/// liquid-bundle 1
+/// version 1.2.0
+/// feature jekyll
+/// checksum c8314fd0cd819300
+/// template 11 header.liquid
+/// <h1>Hi</h1>
+/// template 16 snippets/card.liquid
+/// {{ card.title }}
+/// ```
+///
+/// - The first line identifies the format and its revision.
+/// - Metadata lines follow as `<key> <value>`: an optional `version` and any number of `feature`s
+///   the templates require.  Unknown keys are ignored so newer writers can add them.
+/// - `checksum` ends the metadata.  It is the 64-bit FNV-1a hash, in hex, of everything after the
+///   first line except the `checksum` line itself, so metadata and templates are both covered.
+/// - Each template is a `template <length> <name>` line, then `length` bytes of source and a
+///   newline.  Templates are in name order, so the same templates always make the same bundle.
+///   Names are unique.
+#[derive(Debug, Default, Clone)]
+pub struct BundleWriter {
+    version: Option<String>,
+    features: Vec<String>,
+    templates: BTreeMap<String, String>,
+}
+
+impl BundleWriter {
+    /// Create an empty bundle.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Version the bundle's content, like a theme's release.
+    pub fn version<S: Into<String>>(mut self, version: S) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Record a feature the templates require, like a plugin library.
+    pub fn feature<S: Into<String>>(mut self, feature: S) -> Self {
+        self.features.push(feature.into());
+        self
+    }
+
+    /// Add a partial-template's source.
+    ///
+    /// Returns whether a partial-template was replaced.
+    pub fn add<N, S>(&mut self, name: N, source: S) -> bool
+    where
+        N: Into<String>,
+        S: Into<String>,
+    {
+        self.templates.insert(name.into(), source.into()).is_some()
+    }
+
+    /// Write the bundle.
+    pub fn write_to(&self, writer: &mut dyn io::Write) -> Result<()> {
+        let bytes = self.to_bytes()?;
+        writer
+            .write_all(&bytes)
+            .lossy_chain("Failed to write template bundle")
+    }
+
+    /// Write the bundle into memory.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut body = String::new();
+        for (name, source) in &self.templates {
+            check_line("template name", name)?;
+            body.push_str(&format!("template {} {}\n", source.len(), name));
+            body.push_str(source);
+            body.push('\n');
+        }
+
+        let mut metadata = String::new();
+        if let Some(version) = &self.version {
+            check_line("version", version)?;
+            metadata.push_str(&format!("version {}\n", version));
+        }
+        for feature in &self.features {
+            check_line("feature", feature)?;
+            metadata.push_str(&format!("feature {}\n", feature));
+        }
+
+        let mut bundle = String::new();
+        bundle.push_str(HEADER);
+        bundle.push('\n');
+        bundle.push_str(&metadata);
+        bundle.push_str(&format!(
+            "checksum {:016x}\n",
+            checksum(&[metadata.as_bytes(), body.as_bytes()])
+        ));
+        bundle.push_str(&body);
+        Ok(bundle.into_bytes())
+    }
+}
+
+fn check_line(field: &'static str, value: &str) -> Result<()> {
+    if value.is_empty() || value.contains('\n') {
+        return Error::with_msg("Invalid template bundle field")
+            .context("field", field)
+            .context("value", value.to_owned())
+            .into_err();
+    }
+    Ok(())
+}
+
+/// Partial-template source code read from a bundle written by `BundleWriter`.
+///
+/// The whole bundle is read and its checksum verified up front, so a bundle is loaded entirely or
+/// not at all.
+#[derive(Debug, Default, Clone)]
+pub struct BundleSource {
+    version: Option<String>,
+    features: Vec<String>,
+    checksum: u64,
+    templates: BTreeMap<String, String>,
+}
+
+impl BundleSource {
+    /// Read a bundle.
+    pub fn read(mut reader: impl io::Read) -> Result<Self> {
+        let mut text = String::new();
+        reader
+            .read_to_string(&mut text)
+            .lossy_chain("Failed to read template bundle")?;
+        text.parse()
+    }
+
+    /// The bundle's `BundleWriter::version`.
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// The features the bundle's templates require.
+    pub fn features(&self) -> impl Iterator<Item = &str> {
+        self.features.iter().map(|s| s.as_str())
+    }
+
+    /// The bundle's checksum, identifying its templates.
+    pub fn checksum(&self) -> u64 {
+        self.checksum
+    }
+}
+
+impl str::FromStr for BundleSource {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        let mut rest = text;
+        let header = next_line(&mut rest)?;
+        if header != HEADER {
+            return Error::with_msg("Unsupported template bundle")
+                .context("header", header.to_owned())
+                .into_err();
+        }
+
+        let mut bundle = Self::default();
+        let metadata_start = rest;
+        let metadata = loop {
+            let metadata = &metadata_start[..metadata_start.len() - rest.len()];
+            let line = next_line(&mut rest)?;
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "version" => bundle.version = Some(value.to_owned()),
+                "feature" => bundle.features.push(value.to_owned()),
+                "checksum" => {
+                    bundle.checksum = u64::from_str_radix(value, 16)
+                        .map_err(|_| invalid("Invalid checksum", line))?;
+                    break metadata;
+                }
+                _ => {}
+            }
+        };
+
+        if checksum(&[metadata.as_bytes(), rest.as_bytes()]) != bundle.checksum {
+            return Error::with_msg("Template bundle checksum mismatch").into_err();
+        }
+
+        while !rest.is_empty() {
+            let line = next_line(&mut rest)?;
+            let Some((len, name)) = line
+                .strip_prefix("template ")
+                .and_then(|entry| entry.split_once(' '))
+            else {
+                return invalid("Expected a template", line).into_err();
+            };
+            let len: usize = len
+                .parse()
+                .map_err(|_| invalid("Invalid template length", line))?;
+            let source = rest
+                .get(..len)
+                .filter(|_| rest[len..].starts_with('\n'))
+                .ok_or_else(|| invalid("Truncated template", line))?;
+            if bundle
+                .templates
+                .insert(name.to_owned(), source.to_owned())
+                .is_some()
+            {
+                return invalid("Duplicate template", line).into_err();
+            }
+            rest = &rest[len + 1..];
+        }
+
+        Ok(bundle)
+    }
+}
+
+fn next_line<'t>(rest: &mut &'t str) -> Result<&'t str> {
+    let Some((line, remaining)) = rest.split_once('\n') else {
+        return Error::with_msg("Truncated template bundle").into_err();
+    };
+    *rest = remaining;
+    Ok(line)
+}
+
+fn invalid(msg: &'static str, line: &str) -> Error {
+    Error::with_msg(msg).context("line", line.to_owned())
+}
+
+/// 64-bit FNV-1a, since it is stable across platforms and releases.
+fn checksum(parts: &[&[u8]]) -> u64 {
+    parts
+        .iter()
+        .flat_map(|part| part.iter())
+        .fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+            (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+impl PartialSource for BundleSource {
+    fn contains(&self, name: &str) -> bool {
+        self.templates.contains_key(name)
+    }
+
+    fn names(&self) -> Vec<&str> {
+        self.templates.keys().map(|s| s.as_str()).collect()
+    }
+
+    fn try_get<'a>(&'a self, name: &str) -> Option<borrow::Cow<'a, str>> {
+        self.templates.get(name).map(|s| s.as_str().into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn writer() -> BundleWriter {
+        let mut writer = BundleWriter::new().version("1.2.0").feature("jekyll");
+        writer.add("snippets/card.liquid", "{{ card.title }}\n");
+        writer.add("header.liquid", "<h1>Hi</h1>");
+        writer
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let bytes = writer().to_bytes().unwrap();
+        let bundle = BundleSource::read(bytes.as_slice()).unwrap();
+        assert_eq!(bundle.version(), Some("1.2.0"));
+        assert_eq!(bundle.features().collect::<Vec<_>>(), ["jekyll"]);
+        assert_eq!(bundle.names(), ["header.liquid", "snippets/card.liquid"]);
+        assert_eq!(bundle.get("header.liquid").unwrap(), "<h1>Hi</h1>");
+        assert_eq!(
+            bundle.get("snippets/card.liquid").unwrap(),
+            "{{ card.title }}\n"
+        );
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let first = writer().to_bytes().unwrap();
+        let second = writer().to_bytes().unwrap();
+        assert_eq!(first, second);
+
+        let bundle = BundleSource::read(first.as_slice()).unwrap();
+        let mut other = writer();
+        other.add("footer.liquid", "bye");
+        let other = BundleSource::read(other.to_bytes().unwrap().as_slice()).unwrap();
+        assert_ne!(bundle.checksum(), other.checksum());
+    }
+
+    #[test]
+    fn test_corrupted() {
+        let bytes = writer().to_bytes().unwrap();
+        let text = String::from_utf8(bytes).unwrap();
+        let corrupted = text.replace("Hi", "Ho");
+        assert!(corrupted.parse::<BundleSource>().is_err());
+        let corrupted = text.replace("version 1.2.0", "version 1.3.0");
+        assert!(corrupted.parse::<BundleSource>().is_err());
+        let corrupted = text.replace("feature jekyll", "feature shopify");
+        assert!(corrupted.parse::<BundleSource>().is_err());
+
+        let truncated = &text[..text.len() - 4];
+        assert!(truncated.parse::<BundleSource>().is_err());
+
+        assert!("liquid-bundle 2\nchecksum 0\n"
+            .parse::<BundleSource>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_documented_format() {
+        let mut writer = BundleWriter::new().version("1.2.0").feature("jekyll");
+        writer.add("snippets/card.liquid", "{{ card.title }}");
+        writer.add("header.liquid", "<h1>Hi</h1>");
+        let text = String::from_utf8(writer.to_bytes().unwrap()).unwrap();
+        assert_eq!(
+            text,
+            "liquid-bundle 1
+version 1.2.0
+feature jekyll
+checksum c8314fd0cd819300
+template 11 header.liquid
+<h1>Hi</h1>
+template 16 snippets/card.liquid
+{{ card.title }}
+"
+        );
+    }
+
+    #[test]
+    fn test_duplicate_name() {
+        let body = "template 1 a\nx\ntemplate 1 a\ny\n";
+        let text = format!(
+            "liquid-bundle 1\nchecksum {:016x}\n{}",
+            checksum(&[body.as_bytes()]),
+            body
+        );
+        let error = text.parse::<BundleSource>().unwrap_err();
+        assert!(error.to_string().contains("Duplicate template"));
+    }
+
+    #[test]
+    fn test_invalid_name() {
+        let mut writer = BundleWriter::new();
+        writer.add("bad\nname", "");
+        assert!(writer.to_bytes().is_err());
+    }
+}
@@ -0,0 +1,36 @@
+use std::borrow;
+
+use super::PartialSource;
+
+/// Partial-template source code compiled into the program.
+///
+/// Usually created with `liquid::partials::embed_partials!`.  Names are file paths relative to the
+/// embedded directory, joined with `/`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EmbeddedSource {
+    files: &'static [(&'static str, &'static str)],
+}
+
+impl EmbeddedSource {
+    /// Serve `(name, source)` pairs.
+    pub const fn new(files: &'static [(&'static str, &'static str)]) -> Self {
+        Self { files }
+    }
+}
+
+impl PartialSource for EmbeddedSource {
+    fn contains(&self, name: &str) -> bool {
+        self.files.iter().any(|(n, _)| *n == name)
+    }
+
+    fn names(&self) -> Vec<&str> {
+        self.files.iter().map(|(n, _)| *n).collect()
+    }
+
+    fn try_get<'a>(&'a self, name: &str) -> Option<borrow::Cow<'a, str>> {
+        self.files
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, s)| borrow::Cow::Borrowed(*s))
+    }
+}
@@ -0,0 +1,202 @@
+use std::borrow;
+use std::ffi;
+use std::fs;
+use std::path;
+use std::time;
+
+use super::PartialSource;
+
+/// Partial-template source code read from directories on disk.
+///
+/// Roots are searched in order, so earlier roots override later ones.  For a name like
+/// `snippets/card`, each root is checked for `snippets/card`, then with each extension added
+/// (`snippets/card.liquid`) and then with a leading underscore (`snippets/_card.liquid`).
+///
+/// Names must stay within the roots: absolute paths, `..` and symlinks leading outside of a root
+/// are never read.
+///
+/// `names()` lists each file with one of the extensions by the shortest name that finds it, like
+/// `snippets/card`.  `EagerCompiler` only knows the partial-templates by those names, so use
+/// `LazyCompiler` or `OnDemandCompiler` to also look them up by others, like
+/// `snippets/_card.liquid`.
+#[derive(Debug, Default, Clone)]
+pub struct FileSystemSource {
+    roots: Vec<path::PathBuf>,
+    extensions: Vec<String>,
+    names: Vec<String>,
+}
+
+impl FileSystemSource {
+    /// Read partial-templates from `roots`, in priority order.
+    ///
+    /// The roots are scanned for `names()` now; later changes are only seen when looking up a
+    /// partial-template.
+    pub fn new<I, P>(roots: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<path::PathBuf>,
+    {
+        let mut source = Self {
+            roots: roots.into_iter().map(Into::into).collect(),
+            extensions: vec!["liquid".to_owned()],
+            names: Vec::new(),
+        };
+        source.names = source.scan_names();
+        source
+    }
+
+    /// Change which extensions are tried when a name doesn't match a file (default: `liquid`).
+    ///
+    /// The roots are scanned for `names()` again.
+    pub fn with_extensions<I, S>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.extensions = extensions.into_iter().map(Into::into).collect();
+        self.names = self.scan_names();
+        self
+    }
+
+    /// Find the file for a partial-template.
+    pub fn resolve(&self, name: &str) -> Option<path::PathBuf> {
+        let relative = relative_path(name)?;
+        self.roots.iter().find_map(|root| {
+            self.candidates(&relative)
+                .into_iter()
+                .find_map(|c| contained(root, &c))
+        })
+    }
+
+    fn scan_names(&self) -> Vec<String> {
+        let mut files = Vec::new();
+        for root in &self.roots {
+            collect_files(root, root, &mut files);
+        }
+        let mut names: Vec<_> = files
+            .iter()
+            .filter_map(|(relative, path)| self.name_of(relative, path))
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    /// The shortest name that `resolve`s to the file at `path`, if it has one of the extensions.
+    ///
+    /// Files overridden by an earlier root have none.
+    fn name_of(&self, relative: &path::Path, path: &path::Path) -> Option<String> {
+        let ext = relative.extension()?.to_str()?;
+        if !self.extensions.iter().any(|e| e == ext) {
+            return None;
+        }
+        let stem = relative.file_stem()?.to_str()?;
+        let mut file_names = vec![stem, relative.file_name()?.to_str()?];
+        if let Some(stem) = stem.strip_prefix('_') {
+            file_names.insert(0, stem);
+        }
+
+        let path = path.canonicalize().ok()?;
+        let dir: Vec<_> = relative
+            .parent()?
+            .components()
+            .map(|c| c.as_os_str().to_str())
+            .collect::<Option<_>>()?;
+        file_names.into_iter().find_map(|file_name| {
+            let mut name = dir.clone();
+            name.push(file_name);
+            let name = name.join("/");
+            (self.resolve(&name)? == path).then_some(name)
+        })
+    }
+
+    fn candidates(&self, relative: &path::Path) -> Vec<path::PathBuf> {
+        let file_name = relative
+            .file_name()
+            .expect("relative_path ensures a file name")
+            .to_owned();
+        let mut underscored = ffi::OsString::from("_");
+        underscored.push(&file_name);
+
+        let mut candidates = Vec::new();
+        for file_name in [file_name, underscored] {
+            candidates.push(relative.with_file_name(&file_name));
+            for ext in &self.extensions {
+                let mut file_name = file_name.clone();
+                file_name.push(".");
+                file_name.push(ext);
+                candidates.push(relative.with_file_name(file_name));
+            }
+        }
+        candidates
+    }
+}
+
+impl PartialSource for FileSystemSource {
+    fn contains(&self, name: &str) -> bool {
+        self.resolve(name).is_some()
+    }
+
+    fn names(&self) -> Vec<&str> {
+        self.names.iter().map(|s| s.as_str()).collect()
+    }
+
+    fn try_get<'a>(&'a self, name: &str) -> Option<borrow::Cow<'a, str>> {
+        let path = self.resolve(name)?;
+        fs::read_to_string(path).ok().map(|s| s.into())
+    }
+
+    fn modified(&self, name: &str) -> Option<time::SystemTime> {
+        let path = self.resolve(name)?;
+        fs::metadata(path).ok()?.modified().ok()
+    }
+}
+
+/// Reject names that could leave the root.
+fn relative_path(name: &str) -> Option<path::PathBuf> {
+    let mut relative = path::PathBuf::new();
+    for component in path::Path::new(name).components() {
+        match component {
+            path::Component::Normal(part) => relative.push(part),
+            path::Component::CurDir => {}
+            path::Component::ParentDir | path::Component::RootDir | path::Component::Prefix(_) => {
+                return None;
+            }
+        }
+    }
+    relative.file_name()?;
+    Some(relative)
+}
+
+/// The file at `root/relative`, if it exists and, after following symlinks, is within `root`.
+fn contained(root: &path::Path, relative: &path::Path) -> Option<path::PathBuf> {
+    let path = root.join(relative);
+    if !path.is_file() {
+        return None;
+    }
+    let root = root.canonicalize().ok()?;
+    let path = path.canonicalize().ok()?;
+    path.starts_with(root).then_some(path)
+}
+
+/// Each file under `dir`, relative to `root` and in full.
+fn collect_files(
+    root: &path::Path,
+    dir: &path::Path,
+    files: &mut Vec<(path::PathBuf, path::PathBuf)>,
+) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        // Not following symlinked directories, to avoid cycles
+        if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+            collect_files(root, &path, files);
+        } else if !path.is_file() {
+            continue;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            files.push((relative.to_owned(), path.clone()));
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::borrow;
+use std::time;
+
+use super::PartialSource;
+
+/// Partial-template source code from several sources, in priority order.
+///
+/// A partial-template comes from the first layer that has it, like a site's partial-templates
+/// overriding its theme's.  Prefixing a name with a layer's name, like `theme:header`, starts the
+/// search at that layer, so a partial-template can wrap the one it overrides.
+///
+/// `EagerCompiler` only knows the partial-templates by their `names()`, so use `LazyCompiler` or
+/// `OnDemandCompiler` to look them up with a layer prefix.
+#[derive(Debug, Default)]
+pub struct LayeredSource {
+    layers: Vec<Layer>,
+}
+
+#[derive(Debug)]
+struct Layer {
+    name: String,
+    source: Box<dyn PartialSource + Send + Sync>,
+}
+
+impl LayeredSource {
+    /// Create an empty layered source.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add a layer below the existing ones.
+    pub fn with_layer<N, S>(mut self, name: N, source: S) -> Self
+    where
+        N: Into<String>,
+        S: PartialSource + Send + Sync + 'static,
+    {
+        self.add_layer(name, source);
+        self
+    }
+
+    /// Add a layer below the existing ones.
+    pub fn add_layer<N, S>(&mut self, name: N, source: S)
+    where
+        N: Into<String>,
+        S: PartialSource + Send + Sync + 'static,
+    {
+        self.layers.push(Layer {
+            name: name.into(),
+            source: Box::new(source),
+        });
+    }
+
+    /// The layer that provides a partial-template and the name within that layer.
+    fn resolve<'n>(&self, name: &'n str) -> Option<(&dyn PartialSource, &'n str)> {
+        let (layers, name) = self.layers_for(name);
+        layers
+            .iter()
+            .map(|l| l.source.as_ref() as &dyn PartialSource)
+            .find(|s| s.contains(name))
+            .map(|s| (s, name))
+    }
+
+    fn layers_for<'n>(&self, name: &'n str) -> (&[Layer], &'n str) {
+        if let Some((prefix, rest)) = name.split_once(':') {
+            if let Some(i) = self.layers.iter().position(|l| l.name == prefix) {
+                return (&self.layers[i..], rest);
+            }
+        }
+        (&self.layers, name)
+    }
+}
+
+impl PartialSource for LayeredSource {
+    fn contains(&self, name: &str) -> bool {
+        self.resolve(name).is_some()
+    }
+
+    fn names(&self) -> Vec<&str> {
+        let mut names: Vec<_> = self.layers.iter().flat_map(|l| l.source.names()).collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    fn try_get<'a>(&'a self, name: &str) -> Option<borrow::Cow<'a, str>> {
+        let (source, name) = self.resolve(name)?;
+        source.try_get(name)
+    }
+
+    fn modified(&self, name: &str) -> Option<time::SystemTime> {
+        let (source, name) = self.resolve(name)?;
+        source.modified(name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::partials::InMemorySource;
+
+    use super::*;
+
+    fn source() -> LayeredSource {
+        let mut site = InMemorySource::new();
+        site.add("header", "site header");
+        site.add("footer", "site footer");
+        let mut theme = InMemorySource::new();
+        theme.add("header", "theme header");
+        theme.add("nav", "theme nav");
+        LayeredSource::new()
+            .with_layer("site", site)
+            .with_layer("theme", theme)
+    }
+
+    #[test]
+    fn test_priority() {
+        let source = source();
+        assert_eq!(source.get("header").unwrap(), "site header");
+        assert_eq!(source.get("footer").unwrap(), "site footer");
+        assert_eq!(source.get("nav").unwrap(), "theme nav");
+        assert!(source.try_get("missing").is_none());
+    }
+
+    #[test]
+    fn test_names() {
+        let source = source();
+        assert_eq!(source.names(), ["footer", "header", "nav"]);
+    }
+
+    #[test]
+    fn test_layer_prefix() {
+        let source = source();
+        assert_eq!(source.get("theme:header").unwrap(), "theme header");
+        assert_eq!(source.get("site:nav").unwrap(), "theme nav");
+        assert!(!source.contains("theme:footer"));
+        assert!(!source.contains("other:header"));
+    }
+}
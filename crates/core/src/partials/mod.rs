@@ -1,21 +1,32 @@
 use std::borrow;
 use std::fmt;
 use std::sync;
+use std::time;
 
 use crate::error::Error;
 use crate::error::Result;
 use crate::parser::Language;
 use crate::runtime::PartialStore;
 
+mod bundle;
 mod eager;
+mod embedded;
+mod filesystem;
 mod inmemory;
+mod layered;
 mod lazy;
 mod ondemand;
+mod reloading;
 
+pub use self::bundle::*;
 pub use self::eager::*;
+pub use self::embedded::*;
+pub use self::filesystem::*;
 pub use self::inmemory::*;
+pub use self::layered::*;
 pub use self::lazy::*;
 pub use self::ondemand::*;
+pub use self::reloading::*;
 
 /// Compile a `PartialSource` into a `PartialStore` of `Renderable`s.
 ///
@@ -43,6 +54,13 @@ pub trait PartialSource: fmt::Debug {
     /// Access a partial-template.
     fn try_get<'a>(&'a self, name: &str) -> Option<borrow::Cow<'a, str>>;
 
+    /// When the partial-template was last changed, if known.
+    ///
+    /// This lets caching compilers notice edits without re-reading the partial-template.
+    fn modified(&self, _name: &str) -> Option<time::SystemTime> {
+        None
+    }
+
     /// Access a partial-template
     fn get<'a>(&'a self, name: &str) -> Result<borrow::Cow<'a, str>> {
         self.try_get(name).ok_or_else(|| {
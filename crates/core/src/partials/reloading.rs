@@ -0,0 +1,346 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync;
+use std::time;
+
+use crate::error::Result;
+use crate::parser;
+use crate::parser::Language;
+use crate::runtime;
+use crate::runtime::PartialStore;
+use crate::runtime::Renderable;
+
+use super::PartialCompiler;
+use super::PartialSource;
+
+/// A caching compiler for `PartialSource` that picks up edits.
+///
+/// A cached partial-template is reused until its modification time (see
+/// `PartialSource::modified`) or, when that isn't known, its content changes.  Content modified
+/// within a couple of seconds of being read is re-checked, as the modification time may not have
+/// changed.
+///
+/// This would be useful in cases where:
+/// - Partial-templates are being edited while the application runs, like during development.
+///
+/// Note: partial-compilation error reporting is deferred to render-time so content can still be
+/// generated even when the content is in an intermediate-state.
+#[derive(Debug)]
+pub struct ReloadingCompiler<S: PartialSource> {
+    source: S,
+}
+
+impl<S> ReloadingCompiler<S>
+where
+    S: PartialSource,
+{
+    /// Create a reloading compiler for `PartialSource`.
+    pub fn new(source: S) -> Self {
+        ReloadingCompiler { source }
+    }
+}
+
+impl<S> ReloadingCompiler<S>
+where
+    S: PartialSource + Default,
+{
+    /// Create an empty compiler for `PartialSource`.
+    pub fn empty() -> Self {
+        Default::default()
+    }
+}
+
+impl<S> Default for ReloadingCompiler<S>
+where
+    S: PartialSource + Default,
+{
+    fn default() -> Self {
+        Self {
+            source: Default::default(),
+        }
+    }
+}
+
+impl<S> ::std::ops::Deref for ReloadingCompiler<S>
+where
+    S: PartialSource,
+{
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.source
+    }
+}
+
+impl<S> ::std::ops::DerefMut for ReloadingCompiler<S>
+where
+    S: PartialSource,
+{
+    fn deref_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+}
+
+impl<S> PartialCompiler for ReloadingCompiler<S>
+where
+    S: PartialSource + Send + Sync + 'static,
+{
+    fn compile(self, language: sync::Arc<Language>) -> Result<Box<dyn PartialStore + Send + Sync>> {
+        let store = ReloadingStore {
+            language,
+            source: self.source,
+            cache: sync::Mutex::new(Default::default()),
+        };
+        Ok(Box::new(store))
+    }
+
+    fn source(&self) -> &dyn PartialSource {
+        &self.source
+    }
+}
+
+/// Filesystems may only record modification times to the nearest couple of seconds, so an edit
+/// made this soon after a partial-template was read might not change its modification time.
+const MODIFIED_GRANULARITY: time::Duration = time::Duration::from_secs(2);
+
+struct CacheEntry {
+    modified: Option<time::SystemTime>,
+    read_at: time::SystemTime,
+    hash: u64,
+    template: Result<sync::Arc<dyn Renderable>>,
+}
+
+impl CacheEntry {
+    /// Whether the content can't have changed since it was read.
+    fn is_fresh(&self, modified: Option<time::SystemTime>) -> bool {
+        match modified {
+            Some(modified) if self.modified == Some(modified) => {
+                modified + MODIFIED_GRANULARITY <= self.read_at
+            }
+            _ => false,
+        }
+    }
+}
+
+struct ReloadingStore<S: PartialSource> {
+    language: sync::Arc<Language>,
+    source: S,
+    cache: sync::Mutex<HashMap<String, CacheEntry>>,
+}
+
+impl<S> ReloadingStore<S>
+where
+    S: PartialSource,
+{
+    fn get_or_create(&self, name: &str) -> Result<sync::Arc<dyn Renderable>> {
+        let modified = self.source.modified(name);
+        {
+            let cache = self.cache.lock().expect("not to be poisoned and reused");
+            if let Some(entry) = cache.get(name) {
+                if entry.is_fresh(modified) {
+                    return entry.template.clone();
+                }
+            }
+        }
+
+        let read_at = time::SystemTime::now();
+        let s = match self.source.get(name) {
+            Ok(s) => s,
+            Err(err) => {
+                let mut cache = self.cache.lock().expect("not to be poisoned and reused");
+                cache.remove(name);
+                return Err(err);
+            }
+        };
+        let s = s.as_ref();
+        let hash = hash(s);
+        {
+            let mut cache = self.cache.lock().expect("not to be poisoned and reused");
+            if let Some(entry) = cache.get_mut(name) {
+                if entry.hash == hash {
+                    entry.modified = modified;
+                    entry.read_at = read_at;
+                    return entry.template.clone();
+                }
+            }
+        }
+
+        // Not holding the lock while parsing; a racing parse of the same partial is harmless.
+        let template = parser::parse(s, &self.language)
+            .map(runtime::Template::new)
+            .map(sync::Arc::new)
+            .map(|t| t as sync::Arc<dyn Renderable>);
+        let mut cache = self.cache.lock().expect("not to be poisoned and reused");
+        cache.insert(
+            name.to_owned(),
+            CacheEntry {
+                modified,
+                read_at,
+                hash,
+                template: template.clone(),
+            },
+        );
+        template
+    }
+}
+
+fn hash(s: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    s.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl<S> PartialStore for ReloadingStore<S>
+where
+    S: PartialSource,
+{
+    fn contains(&self, name: &str) -> bool {
+        self.source.contains(name)
+    }
+
+    fn names(&self) -> Vec<&str> {
+        self.source.names()
+    }
+
+    fn try_get(&self, name: &str) -> Option<sync::Arc<dyn Renderable>> {
+        if !self.source.contains(name) {
+            return None;
+        }
+        self.get_or_create(name).ok()
+    }
+
+    fn get(&self, name: &str) -> Result<sync::Arc<dyn Renderable>> {
+        self.get_or_create(name)
+    }
+}
+
+impl<S> fmt::Debug for ReloadingStore<S>
+where
+    S: PartialSource,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.source.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::borrow;
+    use std::sync;
+    use std::time;
+
+    use crate::partials;
+    use crate::runtime::PartialStore;
+    use crate::Language;
+
+    use super::*;
+
+    #[derive(Default, Debug)]
+    struct TestSource {
+        content: sync::Mutex<String>,
+        modified: sync::Mutex<Option<time::SystemTime>>,
+    }
+
+    impl TestSource {
+        fn edit(&self, content: &str, modified: Option<time::SystemTime>) {
+            *self.content.lock().unwrap() = content.to_owned();
+            *self.modified.lock().unwrap() = modified;
+        }
+    }
+
+    impl partials::PartialSource for TestSource {
+        fn contains(&self, name: &str) -> bool {
+            name == "example.txt"
+        }
+
+        fn names(&self) -> Vec<&str> {
+            vec!["example.txt"]
+        }
+
+        fn try_get<'a>(&'a self, name: &str) -> Option<borrow::Cow<'a, str>> {
+            self.contains(name)
+                .then(|| self.content.lock().unwrap().clone().into())
+        }
+
+        fn modified(&self, _name: &str) -> Option<time::SystemTime> {
+            *self.modified.lock().unwrap()
+        }
+    }
+
+    fn store() -> ReloadingStore<TestSource> {
+        ReloadingStore {
+            language: sync::Arc::new(Language::empty()),
+            source: TestSource::default(),
+            cache: sync::Mutex::new(Default::default()),
+        }
+    }
+
+    #[test]
+    fn test_reuses_unmodified() {
+        let store = store();
+        let time = time::SystemTime::UNIX_EPOCH;
+        store.source.edit("Hello", Some(time));
+        let first = store.get("example.txt").unwrap();
+
+        // Same time, so the content isn't even checked
+        store.source.edit("Goodbye", Some(time));
+        let second = store.get("example.txt").unwrap();
+        assert!(sync::Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn test_reloads_modified() {
+        let store = store();
+        let time = time::SystemTime::UNIX_EPOCH;
+        store.source.edit("Hello", Some(time));
+        let first = store.get("example.txt").unwrap();
+
+        // Touched but not changed
+        store
+            .source
+            .edit("Hello", Some(time + time::Duration::from_secs(1)));
+        let second = store.get("example.txt").unwrap();
+        assert!(sync::Arc::ptr_eq(&first, &second));
+
+        store
+            .source
+            .edit("Goodbye", Some(time + time::Duration::from_secs(2)));
+        let third = store.get("example.txt").unwrap();
+        assert!(!sync::Arc::ptr_eq(&first, &third));
+    }
+
+    #[test]
+    fn test_rechecks_recently_modified() {
+        let store = store();
+        let time = time::SystemTime::now();
+        store.source.edit("Hello", Some(time));
+        let first = store.get("example.txt").unwrap();
+
+        // Edited within the modification time's granularity
+        store.source.edit("Goodbye", Some(time));
+        let second = store.get("example.txt").unwrap();
+        assert!(!sync::Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn test_reloads_changed_content() {
+        let store = store();
+        store.source.edit("Hello", None);
+        let first = store.get("example.txt").unwrap();
+        let second = store.get("example.txt").unwrap();
+        assert!(sync::Arc::ptr_eq(&first, &second));
+
+        store.source.edit("Goodbye", None);
+        let third = store.get("example.txt").unwrap();
+        assert!(!sync::Arc::ptr_eq(&first, &third));
+    }
+
+    #[test]
+    fn test_missing() {
+        let store = store();
+        assert!(store.try_get("missing.txt").is_none());
+        assert!(store.get("missing.txt").is_err());
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::BTreeSet;
+
+use crate::model::KString;
+
+use super::PartialStore;
+
+/// What a template depends on, as found by static analysis.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Dependencies {
+    variables: BTreeSet<Vec<KString>>,
+    partials: BTreeSet<KString>,
+    filters: BTreeSet<KString>,
+    tags: BTreeSet<KString>,
+    incomplete: bool,
+}
+
+impl Dependencies {
+    /// Create an empty set of dependencies.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Paths of the global variables referenced.
+    ///
+    /// A path stops at the first index that can't be known without rendering, so
+    /// `{{ page.tags[i] }}` is reported as `["page", "tags"]` (plus `["i"]`).
+    pub fn variables(&self) -> impl Iterator<Item = &[KString]> {
+        self.variables.iter().map(|p| p.as_slice())
+    }
+
+    /// Names of the global variables referenced.
+    pub fn roots(&self) -> impl Iterator<Item = &str> {
+        itertools::Itertools::dedup(self.variables.iter().map(|p| p[0].as_str()))
+    }
+
+    /// Names of the partial-templates pulled in, including through other partials.
+    ///
+    /// Only partials named by a literal are known.
+    pub fn partials(&self) -> impl Iterator<Item = &str> {
+        self.partials.iter().map(|s| s.as_str())
+    }
+
+    /// Names of the filters used.
+    pub fn filters(&self) -> impl Iterator<Item = &str> {
+        self.filters.iter().map(|s| s.as_str())
+    }
+
+    /// Names of the tags and blocks used.
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().map(|s| s.as_str())
+    }
+
+    /// Whether everything the template depends on is reported.
+    ///
+    /// Tags that don't implement `Renderable::analyze` may use variables that aren't.
+    pub fn is_complete(&self) -> bool {
+        !self.incomplete
+    }
+}
+
+/// State for statically analyzing a template.
+///
+/// Tracks which names are in scope so that loop variables and locally assigned names are not
+/// reported as global variables.
+pub struct Analyzer<'p> {
+    partials: &'p dyn PartialStore,
+    scopes: Vec<BTreeSet<KString>>,
+    sandboxed: bool,
+    active_partials: Vec<KString>,
+    dependencies: Dependencies,
+}
+
+impl<'p> Analyzer<'p> {
+    /// Create an analyzer without any partial-templates.
+    pub fn new() -> Self {
+        Self {
+            partials: &super::NullPartials,
+            scopes: vec![Default::default()],
+            sandboxed: false,
+            active_partials: Default::default(),
+            dependencies: Default::default(),
+        }
+    }
+
+    /// Resolve partial-templates through `partials`.
+    pub fn set_partials<'n>(self, partials: &'n dyn PartialStore) -> Analyzer<'n> {
+        Analyzer {
+            partials,
+            scopes: self.scopes,
+            sandboxed: self.sandboxed,
+            active_partials: self.active_partials,
+            dependencies: self.dependencies,
+        }
+    }
+
+    /// Partial templates for inclusion.
+    pub fn partials(&self) -> &dyn PartialStore {
+        self.partials
+    }
+
+    /// Record a reference to a variable.
+    ///
+    /// `path` is ignored if its root is in scope.
+    pub fn variable(&mut self, path: Vec<KString>) {
+        let Some(root) = path.first() else {
+            return;
+        };
+        if self.sandboxed || self.scopes.iter().any(|s| s.contains(root)) {
+            return;
+        }
+        self.dependencies.variables.insert(path);
+    }
+
+    /// Record the use of a filter.
+    pub fn filter(&mut self, name: &str) {
+        self.dependencies.filters.insert(KString::from_ref(name));
+    }
+
+    /// Record the use of a tag or block.
+    pub fn tag(&mut self, name: &str) {
+        self.dependencies.tags.insert(KString::from_ref(name));
+    }
+
+    /// Record that some dependencies can't be reported.
+    pub fn incomplete(&mut self) {
+        self.dependencies.incomplete = true;
+    }
+
+    /// Bring a variable into scope for the rest of the template, like `assign`.
+    pub fn set_global(&mut self, name: KString) {
+        self.scopes[0].insert(name);
+    }
+
+    /// Bring a variable into scope until the next `pop_scope`, like a loop variable.
+    pub fn declare(&mut self, name: KString) {
+        self.scopes
+            .last_mut()
+            .expect("there is always a global scope")
+            .insert(name);
+    }
+
+    /// Start a nested scope.
+    pub fn push_scope(&mut self) {
+        self.scopes.push(Default::default());
+    }
+
+    /// End the current nested scope.
+    pub fn pop_scope(&mut self) {
+        assert!(self.scopes.len() > 1, "cannot pop the global scope");
+        self.scopes.pop();
+    }
+
+    /// Record a partial-template and analyze it within the current scope, like `include`.
+    ///
+    /// `variables` are brought into scope for the partial.
+    pub fn include_partial(&mut self, name: &str, variables: &[KString]) {
+        self.push_scope();
+        for var in variables {
+            self.declare(var.clone());
+        }
+        self.analyze_partial(name);
+        self.pop_scope();
+    }
+
+    /// Record a partial-template and analyze it within an isolated scope, like `render`.
+    ///
+    /// Only `variables` are visible to the partial, so none of its variables are globals.
+    pub fn render_partial(&mut self, name: &str, variables: &[KString]) {
+        let scope = variables.iter().cloned().collect();
+        let scopes = std::mem::replace(&mut self.scopes, vec![scope]);
+        let sandboxed = std::mem::replace(&mut self.sandboxed, true);
+        self.analyze_partial(name);
+        self.sandboxed = sandboxed;
+        self.scopes = scopes;
+    }
+
+    fn analyze_partial(&mut self, name: &str) {
+        let name = KString::from_ref(name);
+        self.dependencies.partials.insert(name.clone());
+        if self.active_partials.contains(&name) {
+            // Recursive partials would never finish.
+            return;
+        }
+        if let Some(partial) = self.partials.try_get(name.as_str()) {
+            self.active_partials.push(name);
+            partial.analyze(self);
+            self.active_partials.pop();
+        }
+    }
+
+    /// The dependencies found.
+    pub fn into_dependencies(self) -> Dependencies {
+        self.dependencies
+    }
+}
+
+impl Default for Analyzer<'static> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
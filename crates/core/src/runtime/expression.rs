@@ -7,6 +7,7 @@ use crate::model::ValueCow;
 use crate::model::ValueView;
 
 use super::variable::Variable;
+use super::Analyzer;
 use super::Runtime;
 
 /// An un-evaluated `Value`.
@@ -62,6 +63,13 @@ impl Expression {
         };
         Ok(val)
     }
+
+    /// Report the variables this depends on.
+    pub fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        if let Expression::Variable(ref x) = self {
+            x.analyze(analyzer);
+        }
+    }
 }
 
 impl fmt::Display for Expression {
@@ -3,18 +3,22 @@
 #![warn(missing_docs)]
 #![warn(unused_extern_crates)]
 
+mod analysis;
 mod expression;
 mod partials;
 mod renderable;
 mod runtime;
 mod stack;
+mod taint;
 mod template;
 mod variable;
 
+pub use self::analysis::*;
 pub use self::expression::*;
 pub use self::partials::*;
 pub use self::renderable::*;
 pub use self::runtime::*;
 pub use self::stack::*;
+pub use self::taint::*;
 pub use self::template::*;
 pub use self::variable::*;
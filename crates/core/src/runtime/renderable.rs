@@ -3,6 +3,7 @@ use std::io::Write;
 
 use crate::error::Result;
 
+use super::Analyzer;
 use super::Runtime;
 
 /// Any object (tag/block) that can be rendered by liquid must implement this trait.
@@ -16,4 +17,12 @@ pub trait Renderable: Send + Sync + Debug {
 
     /// Renders the Renderable instance given a Liquid runtime.
     fn render_to(&self, writer: &mut dyn Write, runtime: &dyn Runtime) -> Result<()>;
+
+    /// Report the variables, partial-templates, filters and tags this depends on.
+    ///
+    /// The parser records the name of each tag and block, so this only needs to report what
+    /// is used inside it.  By default, the dependencies are reported as incomplete.
+    fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        analyzer.incomplete();
+    }
 }
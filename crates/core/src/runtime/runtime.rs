@@ -15,6 +15,21 @@ pub trait Runtime {
     /// The name of the currently active template.
     fn name(&self) -> Option<crate::model::KStringRef<'_>>;
 
+    /// The time zone to display dates in, if not as-is.
+    fn timezone(&self) -> Option<crate::model::TimeZone> {
+        None
+    }
+
+    /// The collation to order strings by, if not by code point.
+    fn collation(&self) -> Option<&crate::model::Collation> {
+        None
+    }
+
+    /// How to handle a tainted value reaching the output.
+    fn taint_mode(&self) -> super::TaintMode {
+        super::TaintMode::Lax
+    }
+
     /// All available values
     fn roots(&self) -> std::collections::BTreeSet<crate::model::KStringCow<'_>>;
     /// Recursively index into the stack.
@@ -47,6 +62,18 @@ impl<'r, R: Runtime + ?Sized> Runtime for &'r R {
         <R as Runtime>::name(self)
     }
 
+    fn timezone(&self) -> Option<crate::model::TimeZone> {
+        <R as Runtime>::timezone(self)
+    }
+
+    fn collation(&self) -> Option<&crate::model::Collation> {
+        <R as Runtime>::collation(self)
+    }
+
+    fn taint_mode(&self) -> super::TaintMode {
+        <R as Runtime>::taint_mode(self)
+    }
+
     fn roots(&self) -> std::collections::BTreeSet<crate::model::KStringCow<'_>> {
         <R as Runtime>::roots(self)
     }
@@ -84,6 +111,10 @@ impl<'r, R: Runtime + ?Sized> Runtime for &'r R {
 pub struct RuntimeBuilder<'g, 'p> {
     globals: Option<&'g dyn ObjectView>,
     partials: Option<&'p dyn PartialStore>,
+    name: Option<crate::model::KString>,
+    timezone: Option<crate::model::TimeZone>,
+    collation: Option<crate::model::Collation>,
+    taint_mode: super::TaintMode,
 }
 
 impl<'c, 'g: 'c, 'p: 'c> RuntimeBuilder<'g, 'p> {
@@ -92,6 +123,10 @@ impl<'c, 'g: 'c, 'p: 'c> RuntimeBuilder<'g, 'p> {
         Self {
             globals: None,
             partials: None,
+            name: None,
+            timezone: None,
+            collation: None,
+            taint_mode: super::TaintMode::Lax,
         }
     }
 
@@ -100,6 +135,10 @@ impl<'c, 'g: 'c, 'p: 'c> RuntimeBuilder<'g, 'p> {
         RuntimeBuilder {
             globals: Some(values),
             partials: self.partials,
+            name: self.name,
+            timezone: self.timezone,
+            collation: self.collation,
+            taint_mode: self.taint_mode,
         }
     }
 
@@ -108,18 +147,60 @@ impl<'c, 'g: 'c, 'p: 'c> RuntimeBuilder<'g, 'p> {
         RuntimeBuilder {
             globals: self.globals,
             partials: Some(values),
+            name: self.name,
+            timezone: self.timezone,
+            collation: self.collation,
+            taint_mode: self.taint_mode,
         }
     }
 
+    /// Name the template being rendered, like its path.
+    ///
+    /// See `Runtime::name`.
+    pub fn set_name<S: Into<crate::model::KString>>(mut self, name: S) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Display dates in `tz`, like a site's time zone.
+    ///
+    /// See `Runtime::timezone`.
+    pub fn set_timezone(mut self, tz: crate::model::TimeZone) -> Self {
+        self.timezone = Some(tz);
+        self
+    }
+
+    /// Order strings by `collation`, like a site's language.
+    ///
+    /// See `Runtime::collation`.
+    pub fn set_collation(mut self, collation: crate::model::Collation) -> Self {
+        self.collation = Some(collation);
+        self
+    }
+
+    /// Check that tainted values are escaped before they are output.
+    ///
+    /// See `Runtime::taint_mode`.
+    pub fn set_taint_mode(mut self, mode: super::TaintMode) -> Self {
+        self.taint_mode = mode;
+        self
+    }
+
     /// Create the `Runtime`.
     pub fn build(self) -> impl Runtime + 'c {
         let partials = self.partials.unwrap_or(&NullPartials);
         let runtime = RuntimeCore {
             partials,
+            timezone: self.timezone,
+            collation: self.collation,
+            taint_mode: self.taint_mode,
             ..Default::default()
         };
         let runtime = super::IndexFrame::new(runtime);
-        let runtime = super::StackFrame::new(runtime, self.globals.unwrap_or(&NullObject));
+        let mut runtime = super::StackFrame::new(runtime, self.globals.unwrap_or(&NullObject));
+        if let Some(name) = self.name {
+            runtime = runtime.with_name(name);
+        }
         super::GlobalFrame::new(runtime)
     }
 }
@@ -206,6 +287,9 @@ impl Default for RuntimeBuilder<'static, 'static> {
 /// Processing runtime for a template.
 pub struct RuntimeCore<'g> {
     partials: &'g dyn PartialStore,
+    timezone: Option<crate::model::TimeZone>,
+    collation: Option<crate::model::Collation>,
+    taint_mode: super::TaintMode,
 
     registers: Registers,
 }
@@ -233,6 +317,18 @@ impl<'g> Runtime for RuntimeCore<'g> {
         None
     }
 
+    fn timezone(&self) -> Option<crate::model::TimeZone> {
+        self.timezone
+    }
+
+    fn collation(&self) -> Option<&crate::model::Collation> {
+        self.collation.as_ref()
+    }
+
+    fn taint_mode(&self) -> super::TaintMode {
+        self.taint_mode
+    }
+
     fn roots(&self) -> std::collections::BTreeSet<crate::model::KStringCow<'_>> {
         // Indexes don't count
         std::collections::BTreeSet::new()
@@ -274,6 +370,9 @@ impl<'g> Default for RuntimeCore<'g> {
     fn default() -> Self {
         Self {
             partials: &NullPartials,
+            timezone: None,
+            collation: None,
+            taint_mode: super::TaintMode::Lax,
             registers: Default::default(),
         }
     }
@@ -341,7 +440,7 @@ pub enum Interrupt {
 }
 
 #[derive(Copy, Clone, Debug)]
-struct NullPartials;
+pub(crate) struct NullPartials;
 
 impl PartialStore for NullPartials {
     fn contains(&self, _name: &str) -> bool {
@@ -40,6 +40,18 @@ impl<P: super::Runtime, O: ObjectView> super::Runtime for StackFrame<P, O> {
             .or_else(|| self.parent.name())
     }
 
+    fn timezone(&self) -> Option<crate::model::TimeZone> {
+        self.parent.timezone()
+    }
+
+    fn collation(&self) -> Option<&crate::model::Collation> {
+        self.parent.collation()
+    }
+
+    fn taint_mode(&self) -> super::TaintMode {
+        self.parent.taint_mode()
+    }
+
     fn roots(&self) -> std::collections::BTreeSet<crate::model::KStringCow<'_>> {
         let mut roots = self.parent.roots();
         roots.extend(self.data.keys());
@@ -116,6 +128,18 @@ impl<P: super::Runtime> super::Runtime for GlobalFrame<P> {
         self.parent.name()
     }
 
+    fn timezone(&self) -> Option<crate::model::TimeZone> {
+        self.parent.timezone()
+    }
+
+    fn collation(&self) -> Option<&crate::model::Collation> {
+        self.parent.collation()
+    }
+
+    fn taint_mode(&self) -> super::TaintMode {
+        self.parent.taint_mode()
+    }
+
     fn roots(&self) -> std::collections::BTreeSet<crate::model::KStringCow<'_>> {
         let mut roots = self.parent.roots();
         roots.extend(self.data.borrow().keys().map(|k| k.clone().into()));
@@ -191,6 +215,18 @@ impl<P: super::Runtime> super::Runtime for IndexFrame<P> {
         self.parent.name()
     }
 
+    fn timezone(&self) -> Option<crate::model::TimeZone> {
+        self.parent.timezone()
+    }
+
+    fn collation(&self) -> Option<&crate::model::Collation> {
+        self.parent.collation()
+    }
+
+    fn taint_mode(&self) -> super::TaintMode {
+        self.parent.taint_mode()
+    }
+
     fn roots(&self) -> std::collections::BTreeSet<crate::model::KStringCow<'_>> {
         let mut roots = self.parent.roots();
         roots.extend(self.data.borrow().keys().map(|k| k.clone().into()));
@@ -282,6 +318,18 @@ impl<P: super::Runtime, O: ObjectView> super::Runtime for SandboxedStackFrame<P,
             .or_else(|| self.parent.name())
     }
 
+    fn timezone(&self) -> Option<crate::model::TimeZone> {
+        self.parent.timezone()
+    }
+
+    fn collation(&self) -> Option<&crate::model::Collation> {
+        self.parent.collation()
+    }
+
+    fn taint_mode(&self) -> super::TaintMode {
+        self.parent.taint_mode()
+    }
+
     fn roots(&self) -> std::collections::BTreeSet<crate::model::KStringCow<'_>> {
         let mut roots = std::collections::BTreeSet::new();
         roots.extend(self.data.keys());
@@ -0,0 +1,162 @@
+use std::cell::RefCell;
+use std::fmt;
+
+use crate::error::{Error, Result};
+use crate::model::{Path, ScalarCow, Value, ValueCow, ValueView};
+
+use super::{PartialStore, Registers, Runtime};
+
+/// How to handle a tainted value reaching the output without being escaped.
+///
+/// A value stops being tainted once a filter, like `escape`, turns it into a safe string.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TaintMode {
+    /// Don't check for tainted values.
+    #[default]
+    Lax,
+    /// Record a warning and continue rendering.
+    Warn,
+    /// Fail rendering.
+    Error,
+}
+
+/// Tainted values found while rendering.
+///
+/// See `Runtime::taint_mode` for how they are handled.
+#[derive(Debug, Default)]
+pub struct TaintRegister {
+    warnings: Vec<Error>,
+}
+
+impl TaintRegister {
+    /// Report a tainted value that reached the output.
+    pub fn report(&mut self, mode: TaintMode, error: Error) -> Result<()> {
+        match mode {
+            TaintMode::Lax => Ok(()),
+            TaintMode::Warn => {
+                self.warnings.push(error);
+                Ok(())
+            }
+            TaintMode::Error => Err(error),
+        }
+    }
+
+    /// Take the warnings reported so far.
+    pub fn take_warnings(&mut self) -> Vec<Error> {
+        std::mem::take(&mut self.warnings)
+    }
+}
+
+/// Whether `value` is, or contains, a tainted string.
+pub fn is_tainted(value: &dyn ValueView) -> bool {
+    if let Some(scalar) = value.as_scalar() {
+        scalar.is_tainted()
+    } else if let Some(array) = value.as_array() {
+        array.values().any(is_tainted)
+    } else if let Some(object) = value.as_object() {
+        object.values().any(is_tainted)
+    } else {
+        false
+    }
+}
+
+/// Report that the value of `source` is tainted and was output without being escaped.
+///
+/// For tags that output values; see `Runtime::taint_mode`.
+pub fn report_tainted(runtime: &dyn Runtime, source: &dyn fmt::Display) -> Result<()> {
+    let error = Error::with_msg(format!(
+        "variable '{}' is tainted and was not escaped",
+        source
+    ));
+    runtime
+        .registers()
+        .get_mut::<TaintRegister>()
+        .report(runtime.taint_mode(), error)
+}
+
+/// Records the variables read, like by a filter evaluating its arguments.
+///
+/// Only the paths are kept, so whether their values are tainted is checked once, when it
+/// matters.
+pub(crate) struct TaintTracker<P> {
+    parent: P,
+    reads: RefCell<Vec<Path<'static>>>,
+}
+
+impl<P: Runtime> TaintTracker<P> {
+    pub(crate) fn new(parent: P) -> Self {
+        Self {
+            parent,
+            reads: Default::default(),
+        }
+    }
+
+    /// The variables read since the last call.
+    pub(crate) fn take(&self) -> Vec<Path<'static>> {
+        std::mem::take(&mut self.reads.borrow_mut())
+    }
+
+    fn track(&self, path: &[ScalarCow<'_>]) {
+        if let Some((first, rest)) = path.split_first() {
+            let mut path = Path::with_index(first.as_ref().into_owned());
+            path.extend(rest.iter().map(|index| index.as_ref().into_owned()));
+            self.reads.borrow_mut().push(path);
+        }
+    }
+}
+
+impl<P: Runtime> Runtime for TaintTracker<P> {
+    fn partials(&self) -> &dyn PartialStore {
+        self.parent.partials()
+    }
+
+    fn name(&self) -> Option<crate::model::KStringRef<'_>> {
+        self.parent.name()
+    }
+
+    fn timezone(&self) -> Option<crate::model::TimeZone> {
+        self.parent.timezone()
+    }
+
+    fn collation(&self) -> Option<&crate::model::Collation> {
+        self.parent.collation()
+    }
+
+    fn taint_mode(&self) -> TaintMode {
+        self.parent.taint_mode()
+    }
+
+    fn roots(&self) -> std::collections::BTreeSet<crate::model::KStringCow<'_>> {
+        self.parent.roots()
+    }
+
+    fn try_get(&self, path: &[ScalarCow<'_>]) -> Option<ValueCow<'_>> {
+        self.track(path);
+        self.parent.try_get(path)
+    }
+
+    fn get(&self, path: &[ScalarCow<'_>]) -> Result<ValueCow<'_>> {
+        self.track(path);
+        self.parent.get(path)
+    }
+
+    fn set_global(
+        &self,
+        name: crate::model::KString,
+        val: crate::model::Value,
+    ) -> Option<crate::model::Value> {
+        self.parent.set_global(name, val)
+    }
+
+    fn set_index(&self, name: crate::model::KString, val: Value) -> Option<Value> {
+        self.parent.set_index(name, val)
+    }
+
+    fn get_index<'a>(&'a self, name: &str) -> Option<ValueCow<'a>> {
+        self.parent.get_index(name)
+    }
+
+    fn registers(&self) -> &Registers {
+        self.parent.registers()
+    }
+}
@@ -2,6 +2,7 @@ use std::io::Write;
 
 use crate::error::Result;
 
+use super::Analyzer;
 use super::Renderable;
 use super::Runtime;
 
@@ -37,4 +38,10 @@ impl Renderable for Template {
         }
         Ok(())
     }
+
+    fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        for el in &self.elements {
+            el.analyze(analyzer);
+        }
+    }
 }
@@ -5,6 +5,7 @@ use crate::model::Path;
 use crate::model::Scalar;
 use crate::model::{ValueCow, ValueView};
 
+use super::Analyzer;
 use super::Expression;
 use super::Runtime;
 
@@ -65,6 +66,27 @@ impl Variable {
         }
         Ok(path)
     }
+
+    /// Report the variables this depends on.
+    ///
+    /// The reported path ends at the first index that isn't a literal.
+    pub fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        let mut path = vec![self.variable.to_kstr().into_owned()];
+        let mut is_literal = true;
+        for expr in &self.indexes {
+            match expr {
+                Expression::Literal(ref x) if is_literal => match x.as_scalar() {
+                    Some(s) => path.push(s.to_kstr().into_owned()),
+                    None => is_literal = false,
+                },
+                _ => {
+                    is_literal = false;
+                    expr.analyze(analyzer);
+                }
+            }
+        }
+        analyzer.variable(path);
+    }
 }
 
 impl Extend<Scalar> for Variable {
@@ -0,0 +1,89 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use proc_macro2::*;
+use quote::*;
+use syn::*;
+
+/// `"dir"` or `"dir", crate = path`.
+pub struct EmbedInput {
+    dir: LitStr,
+    krate: Option<syn::Path>,
+}
+
+impl parse::Parse for EmbedInput {
+    fn parse(input: parse::ParseStream<'_>) -> Result<Self> {
+        let dir = input.parse()?;
+        let mut krate = None;
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            input.parse::<Token![crate]>()?;
+            input.parse::<Token![=]>()?;
+            krate = Some(input.parse()?);
+            input.parse::<Option<Token![,]>>()?;
+        }
+        Ok(Self { dir, krate })
+    }
+}
+
+pub fn expand(input: &EmbedInput) -> TokenStream {
+    let files = match collect(&input.dir) {
+        Ok(files) => files,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    let krate = match &input.krate {
+        Some(krate) => krate.to_token_stream(),
+        None => quote! { ::liquid },
+    };
+    let files = files.iter().map(|(name, path)| {
+        let path = path.to_string_lossy();
+        quote! {
+            (#name, include_str!(#path))
+        }
+    });
+    quote! {
+        #krate::partials::EmbeddedSource::new(&[#(#files),*])
+    }
+}
+
+fn collect(dir: &LitStr) -> Result<Vec<(String, PathBuf)>> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| Error::new(dir.span(), "CARGO_MANIFEST_DIR is not set"))?;
+    let root = Path::new(&manifest_dir).join(dir.value());
+    if !root.is_dir() {
+        return Err(Error::new(
+            dir.span(),
+            format!("`{}` is not a directory", root.display()),
+        ));
+    }
+
+    let mut paths = Vec::new();
+    collect_paths(&root, &mut paths).map_err(|err| Error::new(dir.span(), err))?;
+    paths.sort_unstable();
+
+    let mut files = Vec::new();
+    for path in paths {
+        let name = path
+            .strip_prefix(&root)
+            .expect("collected within root")
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        files.push((name, path));
+    }
+    Ok(files)
+}
+
+fn collect_paths(dir: &Path, paths: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            collect_paths(&path, paths)?;
+        } else if path.is_file() {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
@@ -2,6 +2,7 @@
 
 extern crate proc_macro;
 
+mod embed_partials;
 mod filter;
 mod filter_parameters;
 pub(crate) mod helpers;
@@ -250,26 +251,118 @@ pub fn derive_display_filter(item: TokenStream) -> TokenStream {
     filter::display::derive(&input).into()
 }
 
-#[proc_macro_derive(CoreValueView)]
+#[proc_macro_derive(CoreValueView, attributes(liquid))]
 pub fn derive_core_value_view(item: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(item as syn::DeriveInput);
     value_view::core_derive(&input).into()
 }
 
-#[proc_macro_derive(CoreObjectView)]
+#[proc_macro_derive(CoreObjectView, attributes(liquid))]
 pub fn derive_core_object_view(item: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(item as syn::DeriveInput);
     object_view::core_derive(&input).into()
 }
 
-#[proc_macro_derive(ValueView)]
+/// Implements `ValueView` for a type that also derives `ObjectView`.
+///
+/// The type renders as an object; see `ObjectView` for the `#[liquid(...)]` attributes.
+#[proc_macro_derive(ValueView, attributes(liquid))]
 pub fn derive_value_view(item: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(item as syn::DeriveInput);
     value_view::derive(&input).into()
 }
 
-#[proc_macro_derive(ObjectView)]
+/// Implements `ObjectView`, exposing each field under its name.
+///
+/// Fields may be configured with `#[liquid(...)]`:
+///     - `rename = "..."` -> the key to expose the field under
+///     - `skip` -> leave the field out
+///     - `skip_if = "..."` -> leave the field out when the given function, called with a
+/// reference to the field, returns `true`
+///     - `flatten` -> merge the keys of the field, which must implement `ObjectView`, into
+/// this object
+///
+/// On the type itself:
+///     - `getter = "..."` -> (repeatable) expose what a method borrows from `self` under the
+/// method's name.  The method takes `&self` and returns `&T` for some `T: ValueView`.  Since
+/// `ObjectView` only hands out references, a getter cannot return a newly computed value;
+/// keep computed values in a skipped field, like a `OnceCell`, and borrow them from there.
+///     - `tag = "..."` -> for enums, the key holding the variant name (defaults to `"type"`)
+///
+/// Getters, the tag and fields must not share a key, except through `flatten`.
+///
+/// Enums are exposed as objects of their tag and the current variant's fields.  Variants must
+/// be unit or have named fields and may be renamed with `#[liquid(rename = "...")]`.
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(Debug, ObjectView, ValueView)]
+/// #[liquid(getter = "full_name")]
+/// struct Author {
+///     #[liquid(rename = "first")]
+///     first_name: String,
+///     last_name: String,
+///     #[liquid(skip_if = "Option::is_none")]
+///     email: Option<String>,
+///     #[liquid(flatten)]
+///     extra: liquid::Object,
+///     // `ObjectView` hands out references, so computed values need somewhere to live.
+///     #[liquid(skip)]
+///     full_name: std::cell::OnceCell<String>,
+/// }
+///
+/// impl Author {
+///     fn full_name(&self) -> &String {
+///         self.full_name
+///             .get_or_init(|| format!("{} {}", self.first_name, self.last_name))
+///     }
+/// }
+///
+/// #[derive(Debug, ObjectView, ValueView)]
+/// #[liquid(tag = "kind")]
+/// enum Shape {
+///     Circle { radius: f64 },
+///     #[liquid(rename = "square")]
+///     Square { side: f64 },
+///     Point,
+/// }
+/// ```
+#[proc_macro_derive(ObjectView, attributes(liquid))]
 pub fn derive_object_view(item: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(item as syn::DeriveInput);
     object_view::derive(&input).into()
 }
+
+/// Embeds a directory of partial-templates into the program, as an `EmbeddedSource`.
+///
+/// The path is relative to the crate's `Cargo.toml`.  Partial-templates are named by their path
+/// within the directory, joined with `/`.  They are not parsed until they are compiled, as the
+/// macro can't know the `Language` they use; use `liquid::partials::test_partials!` to check they
+/// parse in `cargo test`.
+///
+/// The generated code refers to `::liquid`; pass `crate = ::liquid_core`, or the path to a
+/// renamed dependency, to use another.
+///
+/// Edits to embedded files are picked up by cargo but added files are not, until the crate is
+/// rebuilt for another reason.
+///
+/// # Example
+///
+/// ```ignore
+/// static PARTIALS: liquid::partials::EmbeddedSource =
+///     liquid::partials::embed_partials!("templates/partials");
+///
+/// liquid::partials::test_partials!(partials_parse, PARTIALS);
+///
+/// let parser = liquid::ParserBuilder::with_stdlib()
+///     .partials(liquid::partials::EagerCompiler::new(PARTIALS))
+///     .build()?;
+///
+/// let source = liquid_derive::embed_partials!("templates/partials", crate = ::liquid_core);
+/// ```
+#[proc_macro]
+pub fn embed_partials(item: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(item as embed_partials::EmbedInput);
+    embed_partials::expand(&input).into()
+}
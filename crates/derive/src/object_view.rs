@@ -1,191 +1,561 @@
+use crate::helpers::*;
 use proc_macro2::*;
 use quote::*;
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
 use syn::*;
 
 pub fn derive(input: &DeriveInput) -> TokenStream {
-    let DeriveInput {
-        ident,
-        data,
-        generics,
-        ..
-    } = input;
+    match ObjectViewInput::from_input(input) {
+        Ok(input) => input.generate(&quote! { ::liquid }),
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+pub fn core_derive(input: &DeriveInput) -> TokenStream {
+    match ObjectViewInput::from_input(input) {
+        Ok(input) => input.generate(&quote! { ::liquid_core }),
+        Err(err) => err.to_compile_error(),
+    }
+}
 
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+/// A struct or enum, as seen through its `#[liquid(...)]` attributes.
+pub(crate) struct ObjectViewInput<'a> {
+    ident: &'a Ident,
+    generics: &'a Generics,
+    /// The key holding the variant name, for enums.
+    tag: Option<String>,
+    getters: Vec<Ident>,
+    /// One entry for a struct, one per variant for an enum.
+    shapes: Vec<Shape<'a>>,
+}
 
-    let fields = match get_fields(data) {
-        Ok(fields) => fields,
-        Err(err) => return err.to_compile_error(),
-    };
-    let num_fields = fields.len();
+/// The fields available after matching `self` against `pattern`.
+struct Shape<'a> {
+    pattern: TokenStream,
+    /// The variant name, for enums.
+    name: Option<String>,
+    fields: Vec<FieldView<'a>>,
+}
 
-    quote! {
-        impl #impl_generics ::liquid::ObjectView for #ident #ty_generics #where_clause {
-            fn as_value(&self) -> &dyn ::liquid::ValueView {
-                self
-            }
+struct FieldView<'a> {
+    ident: &'a Ident,
+    /// The local `self.#ident` is bound to, as a reference.
+    binding: Ident,
+    /// `None` when flattened.
+    key: Option<String>,
+    skip_if: Option<Path>,
+}
 
-            fn size(&self) -> i64 {
-                #num_fields as i64
-            }
+impl<'a> ObjectViewInput<'a> {
+    pub(crate) fn from_input(input: &'a DeriveInput) -> Result<Self> {
+        let DeriveInput {
+            attrs,
+            ident,
+            data,
+            generics,
+            ..
+        } = input;
 
-            fn keys<'liquid_derive_k>(&'liquid_derive_k self) -> Box<dyn Iterator<Item = ::liquid::model::KStringCow<'liquid_derive_k>> + 'liquid_derive_k> {
-                let mut keys = Vec::with_capacity(#num_fields);
-                #(
-                    keys.push(::liquid::model::KStringCow::from_static(stringify!(#fields)));
-                )*
-                Box::new(keys.into_iter())
-            }
+        let mut tag = AssignOnce::Unset;
+        let mut getters = Vec::new();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("liquid")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("tag") {
+                    assign_str_value(&mut tag, attr, "tag", &meta)?;
+                } else if meta.path.is_ident("getter") {
+                    let method: LitStr = meta.value()?.parse()?;
+                    getters.push(method.parse::<Ident>()?);
+                } else {
+                    return Err(unknown_attribute(attr, &meta));
+                }
+                Ok(())
+            })?;
+        }
 
-            fn values<'liquid_derive_k>(&'liquid_derive_k self) -> Box<dyn Iterator<Item = &'liquid_derive_k dyn ::liquid::ValueView> + 'liquid_derive_k> {
-                let mut values = Vec::<&dyn ::liquid::ValueView>::with_capacity(#num_fields);
-                #(
-                    values.push(&self.#fields);
-                )*
-                Box::new(values.into_iter())
+        let (tag, shapes) = match data {
+            Data::Struct(data) => {
+                if let AssignOnce::Set(_) = tag {
+                    return Err(Error::new_spanned(
+                        data.struct_token,
+                        "`tag` is only supported for enums.",
+                    ));
+                }
+                let fields = match &data.fields {
+                    Fields::Named(fields) => fields,
+                    Fields::Unnamed(fields) => {
+                        return Err(Error::new_spanned(
+                            fields,
+                            "`ObjectView` support for tuple-structs is unimplemented.",
+                        ))
+                    }
+                    Fields::Unit => {
+                        return Err(Error::new_spanned(
+                            &data.fields,
+                            "`ObjectView` support for unit-structs is unimplemented.",
+                        ))
+                    }
+                };
+                let shape = Shape {
+                    pattern: quote! { Self },
+                    name: None,
+                    fields: FieldView::from_fields(fields)?,
+                };
+                (None, vec![shape])
+            }
+            Data::Enum(data) => {
+                if data.variants.is_empty() {
+                    return Err(Error::new_spanned(
+                        data.enum_token,
+                        "`ObjectView` support for empty enums is unimplemented.",
+                    ));
+                }
+                let shapes = data
+                    .variants
+                    .iter()
+                    .map(Shape::from_variant)
+                    .collect::<Result<Vec<_>>>()?;
+                (Some(tag.default_to("type".to_owned())), shapes)
+            }
+            Data::Union(data) => {
+                return Err(Error::new_spanned(
+                    data.union_token,
+                    "Unions cannot impl ObjectView.",
+                ));
             }
+        };
+
+        check_keys(tag.as_deref(), &getters, &shapes)?;
+
+        Ok(Self {
+            ident,
+            generics,
+            tag,
+            getters,
+            shapes,
+        })
+    }
+
+    fn generate(&self, krate: &TokenStream) -> TokenStream {
+        let Self {
+            ident,
+            generics,
+            tag,
+            getters,
+            shapes,
+        } = self;
 
-            fn iter<'liquid_derive_k>(&'liquid_derive_k self) -> Box<dyn Iterator<Item = (::liquid::model::KStringCow<'liquid_derive_k>, &'liquid_derive_k dyn ::liquid::ValueView)> + 'liquid_derive_k> {
-                let mut values = Vec::<(::liquid::model::KStringCow<'liquid_derive_k>, &'liquid_derive_k dyn ::liquid::ValueView)>::with_capacity(#num_fields);
-                #(
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+        let getter_keys: Vec<_> = getters.iter().map(|g| g.unraw().to_string()).collect();
+        let num_fixed = getters.len() + usize::from(tag.is_some());
+
+        let patterns: Vec<_> = shapes.iter().map(|shape| shape.bind(|_| true)).collect();
+        // Plain keys always count as one, without looking at the field.
+        let size_patterns = shapes
+            .iter()
+            .map(|shape| shape.bind(|field| field.key.is_none() || field.skip_if.is_some()));
+
+        let sizes = shapes.iter().map(|shape| {
+            let sizes = shape.fields.iter().map(|field| {
+                let binding = &field.binding;
+                let size = match field.key {
+                    Some(_) => quote! { 1i64 },
+                    None => quote! { #krate::ObjectView::size(#binding) },
+                };
+                match &field.skip_if {
+                    Some(skip_if) => quote! { if #skip_if(#binding) { 0 } else { #size } },
+                    None => size,
+                }
+            });
+            quote! { 0i64 #(+ #sizes)* }
+        });
+
+        let pushes = shapes.iter().map(|shape| {
+            let tag = tag.as_ref().map(|tag| {
+                let name = shape.name.as_ref().expect("enums have variant names");
+                quote! {
                     values.push((
-                        ::liquid::model::KStringCow::from_static(stringify!(#fields)),
-                        &self.#fields,
+                        #krate::model::KStringCow::from_static(#tag),
+                        &#name,
                     ));
-                )*
-                Box::new(values.into_iter())
+                }
+            });
+            let fields = shape.fields.iter().map(|field| {
+                let binding = &field.binding;
+                let push = match &field.key {
+                    Some(key) => quote! {
+                        values.push((#krate::model::KStringCow::from_static(#key), #binding));
+                    },
+                    None => quote! {
+                        values.extend(#krate::ObjectView::iter(#binding));
+                    },
+                };
+                field.guard(push)
+            });
+            quote! {
+                #tag
+                #(#fields)*
             }
+        });
 
-            fn contains_key(&self, index: &str) -> bool {
+        let gets = shapes.iter().map(|shape| {
+            let tag = tag.as_ref().map(|tag| {
+                let name = shape.name.as_ref().expect("enums have variant names");
+                quote! { #tag => return Some(&#name), }
+            });
+            let keys = shape.fields.iter().filter_map(|field| {
+                let key = field.key.as_ref()?;
+                let binding = &field.binding;
+                Some(match &field.skip_if {
+                    Some(skip_if) => {
+                        quote! { #key if !#skip_if(#binding) => return Some(#binding), }
+                    }
+                    None => quote! { #key => return Some(#binding), },
+                })
+            });
+            let flattened = shape
+                .fields
+                .iter()
+                .filter(|field| field.key.is_none())
+                .map(|field| {
+                    let binding = &field.binding;
+                    field.guard(quote! {
+                        if let Some(value) = #krate::ObjectView::get(#binding, index) {
+                            return Some(value);
+                        }
+                    })
+                });
+            quote! {
                 match index {
-                    #(
-                        stringify!(#fields) => true,
-                    )*
-                    _ => false,
+                    #tag
+                    #(#keys)*
+                    _ => {}
                 }
+                #(#flattened)*
             }
+        });
 
-            fn get<'liquid_derive_s>(&'liquid_derive_s self, index: &str) -> Option<&'liquid_derive_s dyn ::liquid::ValueView> {
-                match index {
+        quote! {
+            impl #impl_generics #krate::ObjectView for #ident #ty_generics #where_clause {
+                fn as_value(&self) -> &dyn #krate::ValueView {
+                    self
+                }
+
+                fn size(&self) -> i64 {
+                    let size = match self {
+                        #(
+                            #size_patterns => #sizes,
+                        )*
+                    };
+                    #num_fixed as i64 + size
+                }
+
+                fn keys<'liquid_derive_k>(&'liquid_derive_k self) -> Box<dyn Iterator<Item = #krate::model::KStringCow<'liquid_derive_k>> + 'liquid_derive_k> {
+                    Box::new(#krate::ObjectView::iter(self).map(|(k, _)| k))
+                }
+
+                fn values<'liquid_derive_k>(&'liquid_derive_k self) -> Box<dyn Iterator<Item = &'liquid_derive_k dyn #krate::ValueView> + 'liquid_derive_k> {
+                    Box::new(#krate::ObjectView::iter(self).map(|(_, v)| v))
+                }
+
+                fn iter<'liquid_derive_k>(&'liquid_derive_k self) -> Box<dyn Iterator<Item = (#krate::model::KStringCow<'liquid_derive_k>, &'liquid_derive_k dyn #krate::ValueView)> + 'liquid_derive_k> {
+                    #[allow(unused_mut)]
+                    let mut values = Vec::<(#krate::model::KStringCow<'liquid_derive_k>, &'liquid_derive_k dyn #krate::ValueView)>::new();
+                    match self {
+                        #(
+                            #patterns => { #pushes }
+                        )*
+                    }
                     #(
-                        stringify!(#fields) => Some(&self.#fields),
+                        values.push((
+                            #krate::model::KStringCow::from_static(#getter_keys),
+                            self.#getters(),
+                        ));
                     )*
-                    _ => None,
+                    Box::new(values.into_iter())
+                }
+
+                fn contains_key(&self, index: &str) -> bool {
+                    #krate::ObjectView::get(self, index).is_some()
+                }
+
+                fn get<'liquid_derive_s>(&'liquid_derive_s self, index: &str) -> Option<&'liquid_derive_s dyn #krate::ValueView> {
+                    match self {
+                        #(
+                            #patterns => { #gets }
+                        )*
+                    }
+                    match index {
+                        #(
+                            #getter_keys => Some(self.#getters()),
+                        )*
+                        _ => None,
+                    }
                 }
             }
         }
     }
 }
 
-pub fn core_derive(input: &DeriveInput) -> TokenStream {
-    let DeriveInput {
-        ident,
-        data,
-        generics,
-        ..
-    } = input;
-
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-
-    let fields = match get_fields(data) {
-        Ok(fields) => fields,
-        Err(err) => return err.to_compile_error(),
-    };
-    let num_fields = fields.len();
+impl<'a> Shape<'a> {
+    fn from_variant(variant: &'a Variant) -> Result<Self> {
+        let mut rename = AssignOnce::Unset;
+        for attr in variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("liquid"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    assign_str_value(&mut rename, attr, "rename", &meta)?;
+                } else {
+                    return Err(unknown_attribute(attr, &meta));
+                }
+                Ok(())
+            })?;
+        }
 
-    quote! {
-        impl #impl_generics ::liquid_core::ObjectView for #ident #ty_generics #where_clause {
-            fn as_value(&self) -> &dyn ::liquid_core::ValueView {
-                self
+        let fields = match &variant.fields {
+            Fields::Named(fields) => FieldView::from_fields(fields)?,
+            Fields::Unit => Vec::new(),
+            Fields::Unnamed(fields) => {
+                return Err(Error::new_spanned(
+                    fields,
+                    "`ObjectView` support for tuple variants is unimplemented.",
+                ))
             }
+        };
 
-            fn size(&self) -> i64 {
-                #num_fields as i64
-            }
+        let ident = &variant.ident;
+        Ok(Self {
+            pattern: quote! { Self::#ident },
+            name: Some(rename.default_to(ident.unraw().to_string())),
+            fields,
+        })
+    }
 
-            fn keys<'liquid_derive_k>(&'liquid_derive_k self) -> Box<dyn Iterator<Item = ::liquid_core::model::KStringCow<'liquid_derive_k>> + 'liquid_derive_k> {
-                let mut keys = Vec::with_capacity(#num_fields);
-                #(
-                    keys.push(::liquid_core::model::KStringCow::from_static(stringify!(#fields)));
-                )*
-                Box::new(keys.into_iter())
-            }
+    /// A pattern for `self` that binds the fields `used` selects.
+    fn bind(&self, used: impl Fn(&FieldView<'_>) -> bool) -> TokenStream {
+        let pattern = &self.pattern;
+        let fields = self.fields.iter().filter(|field| used(field));
+        let idents = fields.clone().map(|field| field.ident);
+        let bindings = fields.map(|field| &field.binding);
+        quote! { #pattern { #(#idents: #bindings,)* .. } }
+    }
+}
 
-            fn values<'liquid_derive_k>(&'liquid_derive_k self) -> Box<dyn Iterator<Item = &'liquid_derive_k dyn ::liquid_core::ValueView> + 'liquid_derive_k> {
-                let mut values = Vec::<&dyn ::liquid_core::ValueView>::with_capacity(#num_fields);
-                #(
-                    values.push(&self.#fields);
-                )*
-                Box::new(values.into_iter())
-            }
+impl<'a> FieldView<'a> {
+    /// Parses the fields, leaving out the skipped ones.
+    fn from_fields(fields: &'a FieldsNamed) -> Result<Vec<Self>> {
+        let mut views = Vec::new();
+        for field in fields.named.iter() {
+            let ident = field.ident.as_ref().expect("Fields are named.");
 
-            fn iter<'liquid_derive_k>(&'liquid_derive_k self) -> Box<dyn Iterator<Item = (::liquid_core::model::KStringCow<'liquid_derive_k>, &'liquid_derive_k dyn ::liquid_core::ValueView)> + 'liquid_derive_k> {
-                let mut values = Vec::<(::liquid_core::model::KStringCow<'liquid_derive_k>, &'liquid_derive_k dyn ::liquid_core::ValueView)>::with_capacity(#num_fields);
-                #(
-                    values.push((
-                        ::liquid_core::model::KStringCow::from_static(stringify!(#fields)),
-                        &self.#fields,
-                    ));
-                )*
-                Box::new(values.into_iter())
+            let mut rename = AssignOnce::Unset;
+            let mut skip_if = AssignOnce::Unset;
+            let mut skip = false;
+            let mut flatten = false;
+            for attr in field
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("liquid"))
+            {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("rename") {
+                        assign_str_value(&mut rename, attr, "rename", &meta)?;
+                    } else if meta.path.is_ident("skip_if") {
+                        let path: LitStr = meta.value()?.parse()?;
+                        skip_if.set(path.parse::<Path>()?, || {
+                            Error::new(attr.span(), "parameter `skip_if` was already specified.")
+                        })?;
+                    } else if meta.path.is_ident("skip") {
+                        skip = true;
+                    } else if meta.path.is_ident("flatten") {
+                        flatten = true;
+                    } else {
+                        return Err(unknown_attribute(attr, &meta));
+                    }
+                    Ok(())
+                })?;
             }
 
-            fn contains_key(&self, index: &str) -> bool {
-                match index {
-                    #(
-                        stringify!(#fields) => true,
-                    )*
-                    _ => false,
-                }
+            if skip {
+                continue;
             }
+            let key = match (flatten, rename) {
+                (false, rename) => Some(rename.default_to(ident.unraw().to_string())),
+                (true, AssignOnce::Unset) => None,
+                (true, AssignOnce::Set(_)) => {
+                    return Err(Error::new_spanned(
+                        ident,
+                        "`rename` cannot be used with `flatten`.",
+                    ))
+                }
+            };
+            views.push(Self {
+                ident,
+                binding: format_ident!("liquid_derive_{}", ident),
+                key,
+                skip_if: skip_if.into_option(),
+            });
+        }
+        Ok(views)
+    }
 
-            fn get<'liquid_derive_s>(&'liquid_derive_s self, index: &str) -> Option<&'liquid_derive_s dyn ::liquid_core::ValueView> {
-                match index {
-                    #(
-                        stringify!(#fields) => Some(&self.#fields),
-                    )*
-                    _ => None,
+    /// Wraps `tokens` so they only run when the field isn't skipped.
+    fn guard(&self, tokens: TokenStream) -> TokenStream {
+        let binding = &self.binding;
+        match &self.skip_if {
+            Some(skip_if) => quote! {
+                if !#skip_if(#binding) {
+                    #tokens
                 }
-            }
+            },
+            None => tokens,
         }
     }
 }
 
-pub(crate) fn get_fields(data: &Data) -> Result<Vec<&Ident>> {
-    let fields = match data {
-        Data::Struct(data) => &data.fields,
-        Data::Enum(data) => {
-            return Err(Error::new_spanned(
-                data.enum_token,
-                "`ObjectView` support for `enum` is unimplemented.",
+/// Rejects keys that would hide each other, as only the first would be found by `get`.
+///
+/// Keys of flattened fields are only known at runtime.
+fn check_keys(tag: Option<&str>, getters: &[Ident], shapes: &[Shape<'_>]) -> Result<()> {
+    let mut fixed = Vec::new();
+    for (i, getter) in getters.iter().enumerate() {
+        let key = getter.unraw().to_string();
+        if getters[..i].iter().any(|g| g.unraw() == key) {
+            return Err(Error::new(
+                getter.span(),
+                format!("`getter = \"{}\"` was already specified.", key),
             ));
         }
-        Data::Union(data) => {
-            return Err(Error::new_spanned(
-                data.union_token,
-                "Unions cannot impl ObjectView.",
+        if tag == Some(key.as_str()) {
+            return Err(Error::new(
+                getter.span(),
+                format!("`{}` is already the key of the enum's `tag`.", key),
             ));
         }
-    };
-
-    let fields = match fields {
-        Fields::Named(fields) => fields,
-        Fields::Unnamed(fields) => {
-            return Err(Error::new_spanned(
-                fields,
-                "`ObjectView` support for tuple-structs is unimplemented.",
-            ))
+        fixed.push((key, format!("`getter = \"{}\"`", getter)));
+    }
+    if let Some(tag) = tag {
+        fixed.push((tag.to_owned(), "the enum's `tag`".to_owned()));
+    }
+
+    for shape in shapes {
+        let mut keys: Vec<&str> = Vec::new();
+        for field in &shape.fields {
+            let Some(key) = field.key.as_deref() else {
+                continue;
+            };
+            if let Some((_, owner)) = fixed.iter().find(|(fixed, _)| fixed == key) {
+                return Err(Error::new_spanned(
+                    field.ident,
+                    format!("`{}` is already the key of {}.", key, owner),
+                ));
+            }
+            if keys.contains(&key) {
+                return Err(Error::new_spanned(
+                    field.ident,
+                    format!("`{}` is already the key of another field.", key),
+                ));
+            }
+            keys.push(key);
         }
-        Fields::Unit => {
-            return Err(Error::new_spanned(
-                fields,
-                "`ObjectView` support for unit-structs is unimplemented.",
-            ))
+    }
+    Ok(())
+}
+
+fn unknown_attribute(attr: &Attribute, meta: &meta::ParseNestedMeta) -> Error {
+    Error::new(
+        attr.span(),
+        format!("unknown `{}` liquid attribute", meta.path.to_token_stream()),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn error(input: DeriveInput) -> String {
+        match ObjectViewInput::from_input(&input) {
+            Ok(_) => panic!("keys should collide"),
+            Err(err) => err.to_string(),
         }
-    };
+    }
+
+    #[test]
+    fn getter_colliding_with_field() {
+        let input = parse_quote! {
+            #[liquid(getter = "name")]
+            struct Person {
+                name: String,
+            }
+        };
+        assert_eq!(
+            error(input),
+            "`name` is already the key of `getter = \"name\"`."
+        );
+
+        let input = parse_quote! {
+            #[liquid(getter = "full_name")]
+            struct Person {
+                #[liquid(rename = "full_name")]
+                name: String,
+            }
+        };
+        assert_eq!(
+            error(input),
+            "`full_name` is already the key of `getter = \"full_name\"`."
+        );
+    }
+
+    #[test]
+    fn tag_colliding_with_field() {
+        let input = parse_quote! {
+            enum Shape {
+                Circle { radius: f64 },
+                Square { r#type: String },
+            }
+        };
+        assert_eq!(
+            error(input),
+            "`type` is already the key of the enum's `tag`."
+        );
+
+        let input = parse_quote! {
+            #[liquid(tag = "kind", getter = "kind")]
+            enum Shape {
+                Circle,
+            }
+        };
+        assert_eq!(
+            error(input),
+            "`kind` is already the key of the enum's `tag`."
+        );
+    }
+
+    #[test]
+    fn fields_colliding() {
+        let input = parse_quote! {
+            struct Person {
+                name: String,
+                #[liquid(rename = "name")]
+                nickname: String,
+            }
+        };
+        assert_eq!(error(input), "`name` is already the key of another field.");
+    }
 
-    Ok(fields
-        .named
-        .iter()
-        .map(|field| field.ident.as_ref().expect("Fields are named."))
-        .collect())
+    #[test]
+    fn flattened_fields_are_not_checked() {
+        let input: DeriveInput = parse_quote! {
+            #[liquid(getter = "name")]
+            struct Person {
+                #[liquid(flatten)]
+                extra: Object,
+            }
+        };
+        assert!(ObjectViewInput::from_input(&input).is_ok());
+    }
 }
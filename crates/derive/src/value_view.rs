@@ -2,116 +2,64 @@ use proc_macro2::*;
 use quote::*;
 use syn::*;
 
-pub fn derive(input: &DeriveInput) -> TokenStream {
-    let DeriveInput {
-        ident,
-        data,
-        generics,
-        ..
-    } = input;
-
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+use crate::object_view::ObjectViewInput;
 
-    let fields = match crate::object_view::get_fields(data) {
-        Ok(fields) => fields,
-        Err(err) => return err.to_compile_error(),
-    };
-
-    quote! {
-        impl #impl_generics ::liquid::ValueView for #ident #ty_generics #where_clause {
-            fn as_debug(&self) -> &dyn ::std::fmt::Debug {
-                self
-            }
-
-            fn render(&self) -> ::liquid::model::DisplayCow<'_> {
-                ::liquid::model::DisplayCow::Owned(Box::new(::liquid::model::ObjectRender::new(self)))
-            }
-            fn source(&self) -> ::liquid::model::DisplayCow<'_> {
-                ::liquid::model::DisplayCow::Owned(Box::new(::liquid::model::ObjectSource::new(self)))
-            }
-            fn type_name(&self) -> &'static str {
-                "object"
-            }
-            fn query_state(&self, state: ::liquid::model::State) -> bool {
-                match state {
-                    ::liquid::model::State::Truthy => true,
-                    ::liquid::model::State::DefaultValue |
-                    ::liquid::model::State::Empty |
-                    ::liquid::model::State::Blank => self.size() == 0,
-                }
-            }
-
-            fn to_kstr(&self) -> ::liquid::model::KStringCow<'_> {
-                let s = ::liquid::model::ObjectRender::new(self).to_string();
-                ::liquid::model::KStringCow::from_string(s)
-            }
-            fn to_value(&self) -> ::liquid::model::Value {
-                let mut object = ::liquid::model::Object::new();
-                #(
-                    object.insert(stringify!(#fields).into(), ::liquid::model::ValueView::to_value(&self.#fields));
-                )*
-                ::liquid::model::Value::Object(object)
-            }
-
-            fn as_object(&self) -> Option<&dyn ::liquid::ObjectView> {
-                Some(self)
-            }
-        }
-    }
+pub fn derive(input: &DeriveInput) -> TokenStream {
+    generate(input, &quote! { ::liquid })
 }
 
 pub fn core_derive(input: &DeriveInput) -> TokenStream {
+    generate(input, &quote! { ::liquid_core })
+}
+
+fn generate(input: &DeriveInput, krate: &TokenStream) -> TokenStream {
     let DeriveInput {
-        ident,
-        data,
-        generics,
-        ..
+        ident, generics, ..
     } = input;
 
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-    let fields = match crate::object_view::get_fields(data) {
-        Ok(fields) => fields,
-        Err(err) => return err.to_compile_error(),
-    };
+    if let Err(err) = ObjectViewInput::from_input(input) {
+        return err.to_compile_error();
+    }
 
     quote! {
-        impl #impl_generics ::liquid_core::ValueView for #ident #ty_generics #where_clause {
+        impl #impl_generics #krate::ValueView for #ident #ty_generics #where_clause {
             fn as_debug(&self) -> &dyn ::std::fmt::Debug {
                 self
             }
 
-            fn render(&self) -> ::liquid_core::model::DisplayCow<'_> {
-                ::liquid_core::model::DisplayCow::Owned(Box::new(::liquid_core::model::ObjectRender::new(self)))
+            fn render(&self) -> #krate::model::DisplayCow<'_> {
+                #krate::model::DisplayCow::Owned(Box::new(#krate::model::ObjectRender::new(self)))
             }
-            fn source(&self) -> ::liquid_core::model::DisplayCow<'_> {
-                ::liquid_core::model::DisplayCow::Owned(Box::new(::liquid_core::model::ObjectSource::new(self)))
+            fn source(&self) -> #krate::model::DisplayCow<'_> {
+                #krate::model::DisplayCow::Owned(Box::new(#krate::model::ObjectSource::new(self)))
             }
             fn type_name(&self) -> &'static str {
                 "object"
             }
-            fn query_state(&self, state: ::liquid_core::model::State) -> bool {
+            fn query_state(&self, state: #krate::model::State) -> bool {
                 match state {
-                    ::liquid_core::model::State::Truthy => true,
-                    ::liquid_core::model::State::DefaultValue |
-                    ::liquid_core::model::State::Empty |
-                    ::liquid_core::model::State::Blank => self.size() == 0,
+                    #krate::model::State::Truthy => true,
+                    #krate::model::State::DefaultValue |
+                    #krate::model::State::Empty |
+                    #krate::model::State::Blank => self.size() == 0,
                 }
             }
 
-            fn to_kstr(&self) -> ::liquid_core::model::KStringCow<'_> {
-                let s = ::liquid_core::model::ObjectRender::new(self).to_string();
-                ::liquid_core::model::KStringCow::from_string(s)
-            }
-            fn to_value(&self) -> ::liquid_core::model::Value {
-                let mut object = ::liquid_core::model::Object::new();
-                #(
-                    object.insert(stringify!(#fields).into(), ::liquid_core::model::ValueView::to_value(&self.#fields));
-                )*
-                ::liquid_core::model::Value::Object(object)
+            fn to_kstr(&self) -> #krate::model::KStringCow<'_> {
+                let s = #krate::model::ObjectRender::new(self).to_string();
+                #krate::model::KStringCow::from_string(s)
+            }
+            fn to_value(&self) -> #krate::model::Value {
+                let mut object = #krate::model::Object::new();
+                for (key, value) in #krate::ObjectView::iter(self) {
+                    object.insert(key.into_owned(), #krate::model::ValueView::to_value(value));
+                }
+                #krate::model::Value::Object(object)
             }
 
-            fn as_object(&self) -> Option<&dyn ::liquid_core::ObjectView> {
+            fn as_object(&self) -> Option<&dyn #krate::ObjectView> {
                 Some(self)
             }
         }
@@ -1,3 +1,4 @@
+use liquid_core::model::TimeZone;
 use liquid_core::Expression;
 use liquid_core::Result;
 use liquid_core::Runtime;
@@ -6,7 +7,7 @@ use liquid_core::{
 };
 use liquid_core::{Value, ValueView};
 
-use crate::invalid_input;
+use crate::{invalid_argument, invalid_input};
 
 // liquid-rust proprietary
 
@@ -15,8 +16,7 @@ struct DateInTzArgs {
     #[parameter(description = "The format to return the date in.", arg_type = "str")]
     format: Expression,
     #[parameter(
-        description = "The timezone to convert the date to.",
-        arg_type = "integer"
+        description = "The timezone to convert the date to, as hours from UTC or a name like \"America/New_York\"."
     )]
     timezone: Expression,
 }
@@ -46,11 +46,23 @@ impl Filter for DateInTzFilter {
             .and_then(|s| s.to_date_time())
             .ok_or_else(|| invalid_input("Invalid date format"))?;
 
-        let offset = time::UtcOffset::from_whole_seconds(args.timezone as i32 * 3600)
-            .map_err(|_err| invalid_input("Timezone was too large"))?;
+        let timezone = args
+            .timezone
+            .as_scalar()
+            .ok_or_else(|| invalid_argument("timezone", "Timezone expected"))?;
+        let timezone = match timezone.to_integer() {
+            Some(hours) => i32::try_from(hours)
+                .ok()
+                .and_then(|hours| hours.checked_mul(3600))
+                .and_then(|seconds| time::UtcOffset::from_whole_seconds(seconds).ok())
+                .map(TimeZone::fixed)
+                .ok_or_else(|| invalid_input("Timezone was too large"))?,
+            None => TimeZone::from_name(timezone.to_kstr().as_str())
+                .ok_or_else(|| invalid_argument("timezone", "Unknown timezone"))?,
+        };
 
         let date_str = date
-            .with_offset(offset)
+            .with_timezone(timezone)
             .format(args.format.as_str())
             .map_err(|_err| invalid_input("Invalid format string"))?;
         Ok(Value::scalar(date_str))
@@ -100,9 +112,43 @@ mod tests {
         assert_eq!(unit_result, desired_result);
     }
 
+    #[test]
+    fn unit_date_in_tz_fixed_name() {
+        let unit_result = liquid_core::call_filter!(
+            DateInTz,
+            "13 Jun 2016 12:00:00 +0000",
+            "%Y-%m-%d %H:%M:%S %z",
+            "+05:30"
+        )
+        .unwrap();
+        let desired_result = liquid_core::value!("2016-06-13 17:30:00 +0530");
+        assert_eq!(unit_result, desired_result);
+    }
+
+    #[test]
+    #[cfg(feature = "tz")]
+    fn unit_date_in_tz_named() {
+        let unit_result = liquid_core::call_filter!(
+            DateInTz,
+            "13 Jan 2016 12:00:00 +0000",
+            "%H:%M %z",
+            "America/New_York"
+        )
+        .unwrap();
+        assert_eq!(unit_result, liquid_core::value!("07:00 -0500"));
+        let unit_result = liquid_core::call_filter!(
+            DateInTz,
+            "13 Jun 2016 12:00:00 +0000",
+            "%H:%M %z",
+            "America/New_York"
+        )
+        .unwrap();
+        assert_eq!(unit_result, liquid_core::value!("08:00 -0400"));
+    }
+
     #[test]
     fn unit_date_in_tz_input_not_a_string() {
-        liquid_core::call_filter!(DateInTz, 0f64, "%Y-%m-%d %H:%M:%S %z", 0i64).unwrap_err();
+        liquid_core::call_filter!(DateInTz, true, "%Y-%m-%d %H:%M:%S %z", 0i64).unwrap_err();
     }
 
     #[test]
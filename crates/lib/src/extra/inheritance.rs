@@ -0,0 +1,520 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
+
+use liquid_core::error::ResultLiquidExt;
+use liquid_core::model::KString;
+use liquid_core::runtime::Analyzer;
+use liquid_core::Expression;
+use liquid_core::Language;
+use liquid_core::Renderable;
+use liquid_core::Runtime;
+use liquid_core::Template;
+use liquid_core::ValueView;
+use liquid_core::{BlockReflection, ParseBlock, TagBlock, TagTokenIter};
+use liquid_core::{Error, Result};
+use liquid_core::{ParseTag, TagReflection};
+
+// liquid-rust proprietary
+
+/// `{% extends "parent" %}` renders the parent template, a partial-template, with the `block`s
+/// of the rest of this template overriding the parent's.  `block`s nested within those override
+/// the parent's too.
+///
+/// Content after `extends` and outside of `block`s is not rendered, so `block`s override the
+/// parent's even within tags like `if`.  Content before `extends` has already been rendered, so
+/// `extends` should come first.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ExtendsBlock;
+
+impl ExtendsBlock {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl BlockReflection for ExtendsBlock {
+    fn start_tag(&self) -> &str {
+        "extends"
+    }
+
+    fn end_tag(&self) -> &str {
+        "endextends"
+    }
+
+    fn description(&self) -> &str {
+        "Renders a parent template, overriding its blocks."
+    }
+}
+
+impl ParseBlock for ExtendsBlock {
+    fn parse(
+        &self,
+        mut arguments: TagTokenIter<'_>,
+        mut tokens: TagBlock<'_, '_>,
+        options: &Language,
+    ) -> Result<Box<dyn Renderable>> {
+        let parent = arguments
+            .expect_next("Identifier or literal expected.")?
+            .expect_value()
+            .into_result()?;
+
+        arguments.expect_nothing()?;
+
+        tokens.end_with_template()?;
+        PARSED_BLOCKS.with(|levels| levels.borrow_mut().push(Default::default()));
+        let body = tokens.parse_all(options);
+        let overrides = PARSED_BLOCKS
+            .with(|levels| levels.borrow_mut().pop())
+            .unwrap_or_default();
+        let body = Template::new(body.trace_with(|| format!("{{% extends {} %}}", parent).into())?);
+
+        tokens.assert_empty();
+        Ok(Box::new(Extends {
+            parent,
+            body,
+            overrides,
+        }))
+    }
+
+    fn reflection(&self) -> &dyn BlockReflection {
+        self
+    }
+}
+
+thread_local! {
+    /// The `block`s parsed within each `extends` being parsed, innermost last.
+    static PARSED_BLOCKS: RefCell<Vec<Overrides>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Bodies of `block`s, by name.
+type Overrides = HashMap<KString, Arc<Template>>;
+
+#[derive(Debug)]
+struct Extends {
+    parent: Expression,
+    body: Template,
+    /// Collected while parsing, so overriding `block`s are only rendered where the parent puts
+    /// them.
+    overrides: Overrides,
+}
+
+impl Extends {
+    fn trace(&self) -> String {
+        format!("{{% extends {} %}}", self.parent)
+    }
+}
+
+impl Renderable for Extends {
+    fn render_to(&self, writer: &mut dyn Write, runtime: &dyn Runtime) -> Result<()> {
+        let value = self.parent.evaluate(runtime)?;
+        if !value.is_scalar() {
+            return Error::with_msg("Can only `extends` strings")
+                .context("parent", format!("{}", value.source()))
+                .into_err();
+        }
+        let name = value.to_kstr().into_owned();
+
+        {
+            let mut register = runtime.registers().get_mut::<InheritanceRegister>();
+            if register.chain.contains(&name) {
+                let chain = register
+                    .chain
+                    .iter()
+                    .chain(std::iter::once(&name))
+                    .map(|n| n.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" -> ");
+                return Error::with_msg("Template inheritance cycle")
+                    .context("chain", chain)
+                    .into_err();
+            }
+            register.chain.push(name.clone());
+            register.levels.push(self.overrides.clone());
+        }
+
+        let rendered = runtime
+            .partials()
+            .get(&name)
+            .and_then(|parent| parent.render_to(writer, runtime));
+
+        {
+            let mut register = runtime.registers().get_mut::<InheritanceRegister>();
+            register.chain.pop();
+            register.levels.pop();
+        }
+
+        rendered
+            .trace_with(|| self.trace().into())
+            .context_key("parent")
+            .value_with(|| name.to_string().into())
+    }
+
+    fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        self.body.analyze(analyzer);
+        match self.parent {
+            Expression::Literal(ref name) => {
+                analyzer.include_partial(name.to_kstr().as_str(), &[]);
+            }
+            Expression::Variable(_) => self.parent.analyze(analyzer),
+        }
+    }
+}
+
+/// `{% block name %}...{% endblock %}` is a region that templates `extends`-ing this one can
+/// override.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct BlockBlock;
+
+impl BlockBlock {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl BlockReflection for BlockBlock {
+    fn start_tag(&self) -> &str {
+        "block"
+    }
+
+    fn end_tag(&self) -> &str {
+        "endblock"
+    }
+
+    fn description(&self) -> &str {
+        "A region of a template that can be overridden when extending it."
+    }
+}
+
+impl ParseBlock for BlockBlock {
+    fn parse(
+        &self,
+        mut arguments: TagTokenIter<'_>,
+        mut tokens: TagBlock<'_, '_>,
+        options: &Language,
+    ) -> Result<Box<dyn Renderable>> {
+        let name: KString = arguments
+            .expect_next("Identifier expected")?
+            .expect_identifier()
+            .into_result()?
+            .to_string()
+            .into();
+
+        arguments.expect_nothing()?;
+
+        let body = Template::new(
+            tokens
+                .parse_all(options)
+                .trace_with(|| format!("{{% block {} %}}", &name).into())?,
+        );
+
+        tokens.assert_empty();
+        let body = Arc::new(body);
+        PARSED_BLOCKS.with(|levels| {
+            if let Some(level) = levels.borrow_mut().last_mut() {
+                level.entry(name.clone()).or_insert_with(|| body.clone());
+            }
+        });
+        Ok(Box::new(Block { name, body }))
+    }
+
+    fn reflection(&self) -> &dyn BlockReflection {
+        self
+    }
+}
+
+#[derive(Debug)]
+struct Block {
+    name: KString,
+    body: Arc<Template>,
+}
+
+impl Block {
+    fn trace(&self) -> String {
+        format!("{{% block {} %}}", self.name)
+    }
+}
+
+impl Renderable for Block {
+    fn render_to(&self, writer: &mut dyn Write, runtime: &dyn Runtime) -> Result<()> {
+        let candidates = {
+            let register = runtime.registers().get_mut::<InheritanceRegister>();
+            let mut candidates: Vec<_> = register
+                .levels
+                .iter()
+                .filter_map(|level| level.get(&self.name).cloned())
+                // Nested `block`s were collected as overrides of themselves
+                .filter(|body| !Arc::ptr_eq(body, &self.body))
+                .collect();
+            candidates.push(self.body.clone());
+            candidates
+        };
+
+        render_candidates(candidates, writer, runtime).trace_with(|| self.trace().into())
+    }
+
+    fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        self.body.analyze(analyzer);
+    }
+}
+
+/// `{% super %}` renders what the enclosing `block` overrode.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SuperTag;
+
+impl SuperTag {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl TagReflection for SuperTag {
+    fn tag(&self) -> &'static str {
+        "super"
+    }
+
+    fn description(&self) -> &'static str {
+        "Renders the parent template's content for the enclosing block."
+    }
+}
+
+impl ParseTag for SuperTag {
+    fn parse(
+        &self,
+        mut arguments: TagTokenIter<'_>,
+        _options: &Language,
+    ) -> Result<Box<dyn Renderable>> {
+        arguments.expect_nothing()?;
+        Ok(Box::new(Super))
+    }
+
+    fn reflection(&self) -> &dyn TagReflection {
+        self
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Super;
+
+impl Renderable for Super {
+    fn render_to(&self, writer: &mut dyn Write, runtime: &dyn Runtime) -> Result<()> {
+        let candidates = runtime
+            .registers()
+            .get_mut::<InheritanceRegister>()
+            .supers
+            .last()
+            .cloned();
+        let Some(candidates) = candidates else {
+            return Error::with_msg("{% super %} must be within a block").into_err();
+        };
+        render_candidates(candidates, writer, runtime)
+    }
+
+    fn analyze(&self, _analyzer: &mut Analyzer<'_>) {}
+}
+
+/// Render the most-derived candidate, leaving the rest for `{% super %}`.
+fn render_candidates(
+    mut candidates: Vec<Arc<Template>>,
+    writer: &mut dyn Write,
+    runtime: &dyn Runtime,
+) -> Result<()> {
+    if candidates.is_empty() {
+        return Ok(());
+    }
+    let current = candidates.remove(0);
+
+    runtime
+        .registers()
+        .get_mut::<InheritanceRegister>()
+        .supers
+        .push(candidates);
+    let rendered = current.render_to(writer, runtime);
+    runtime
+        .registers()
+        .get_mut::<InheritanceRegister>()
+        .supers
+        .pop();
+    rendered
+}
+
+/// Tracks the `extends` chain being rendered.
+#[derive(Debug, Default)]
+struct InheritanceRegister {
+    /// Parent templates currently being rendered, to detect cycles.
+    chain: Vec<KString>,
+    /// Block overrides for each `extends`, most-derived first.
+    levels: Vec<Overrides>,
+    /// What `{% super %}` can render for each enclosing `block`.
+    supers: Vec<Vec<Arc<Template>>>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use liquid_core::parser;
+    use liquid_core::partials;
+    use liquid_core::partials::PartialCompiler;
+    use liquid_core::runtime;
+    use liquid_core::runtime::RuntimeBuilder;
+
+    use crate::stdlib;
+
+    fn options() -> Language {
+        let mut options = Language::default();
+        options
+            .blocks
+            .register("extends".to_string(), ExtendsBlock.into());
+        options
+            .blocks
+            .register("block".to_string(), BlockBlock.into());
+        options.tags.register("super".to_string(), SuperTag.into());
+        options
+            .tags
+            .register("increment".to_string(), stdlib::IncrementTag.into());
+        options
+            .tags
+            .register("cycle".to_string(), stdlib::CycleTag.into());
+        options
+            .blocks
+            .register("ifchanged".to_string(), stdlib::IfChangedBlock.into());
+        options
+    }
+
+    fn render(text: &str, sources: &[(&str, &str)]) -> Result<String> {
+        let options = options();
+        let template = parser::parse(text, &options).map(runtime::Template::new)?;
+
+        let mut compiler = partials::LazyCompiler::<partials::InMemorySource>::empty();
+        for (name, source) in sources {
+            compiler.add(*name, *source);
+        }
+        let partials = compiler.compile(Arc::new(options))?;
+        let runtime = RuntimeBuilder::new()
+            .set_partials(partials.as_ref())
+            .build();
+        template.render(&runtime)
+    }
+
+    const BASE: &str = "<{% block title %}Base{% endblock %}|{% block body %}Body{% endblock %}>";
+
+    #[test]
+    fn test_no_overrides() {
+        let output = render("{% extends 'base' %}", &[("base", BASE)]).unwrap();
+        assert_eq!(output, "<Base|Body>");
+    }
+
+    #[test]
+    fn test_override() {
+        let output = render(
+            "{% extends 'base' %}ignored{% block title %}Child{% endblock %}",
+            &[("base", BASE)],
+        )
+        .unwrap();
+        assert_eq!(output, "<Child|Body>");
+    }
+
+    #[test]
+    fn test_super() {
+        let output = render(
+            "{% extends 'base' %}{% block title %}Child {% super %}{% endblock %}",
+            &[("base", BASE)],
+        )
+        .unwrap();
+        assert_eq!(output, "<Child Base|Body>");
+    }
+
+    #[test]
+    fn test_leading_content() {
+        let output = render(
+            "before{% extends 'base' %}after{% block title %}Child{% endblock %}",
+            &[("base", BASE)],
+        )
+        .unwrap();
+        assert_eq!(output, "before<Child|Body>");
+    }
+
+    #[test]
+    fn test_nested_override() {
+        let output = render(
+            "{% extends 'base' %}{% block title %}[{% block body %}Child{% endblock %}]{% endblock %}",
+            &[("base", BASE)],
+        )
+        .unwrap();
+        assert_eq!(output, "<[Child]|Child>");
+    }
+
+    #[test]
+    fn test_overrides_render_once() {
+        let output = render(
+            "{% extends 'base' %}{% block title %}{% increment c %}{% endblock %}",
+            &[("base", BASE)],
+        )
+        .unwrap();
+        assert_eq!(output, "<0|Body>");
+
+        let output = render(
+            "{% extends 'base' %}{% block title %}{% cycle 'a', 'b' %}{% endblock %}",
+            &[("base", BASE)],
+        )
+        .unwrap();
+        assert_eq!(output, "<a|Body>");
+
+        let output = render(
+            "{% extends 'base' %}{% block title %}{% ifchanged %}x{% endifchanged %}{% endblock %}",
+            &[("base", BASE)],
+        )
+        .unwrap();
+        assert_eq!(output, "<x|Body>");
+    }
+
+    #[test]
+    fn test_multiple_levels() {
+        let output = render(
+            "{% extends 'middle' %}{% block title %}Child {% super %}{% endblock %}",
+            &[
+                ("base", BASE),
+                (
+                    "middle",
+                    "{% extends 'base' %}{% block title %}Middle {% super %}{% endblock %}",
+                ),
+            ],
+        )
+        .unwrap();
+        assert_eq!(output, "<Child Middle Base|Body>");
+    }
+
+    #[test]
+    fn test_cycle() {
+        let output = render(
+            "{% extends 'a' %}",
+            &[("a", "{% extends 'b' %}"), ("b", "{% extends 'a' %}")],
+        )
+        .unwrap_err();
+        assert!(output.to_string().contains("Template inheritance cycle"));
+    }
+
+    #[test]
+    fn test_missing_parent() {
+        let output = render("{% extends 'missing' %}", &[]);
+        assert!(output.is_err());
+    }
+
+    #[test]
+    fn test_nested_extends() {
+        let options = options();
+        let template = parser::parse(
+            "{% block title %}{% extends 'base' %}{% endblock %}",
+            &options,
+        );
+        assert!(template.is_err());
+    }
+
+    #[test]
+    fn test_super_outside_block() {
+        let output = render("{% super %}", &[]);
+        assert!(output.is_err());
+    }
+}
@@ -1,3 +1,7 @@
 mod date;
+mod inheritance;
+mod query;
 
 pub use self::date::*;
+pub use self::inheritance::*;
+pub use self::query::*;
@@ -0,0 +1,142 @@
+use liquid_core::model::Query as QueryPath;
+use liquid_core::parser::FilterArguments;
+use liquid_core::Expression;
+use liquid_core::Result;
+use liquid_core::Runtime;
+use liquid_core::{Display_filter, Filter, FilterParameters, FilterReflection, ParseFilter};
+use liquid_core::{Value, ValueView};
+
+use crate::invalid_argument;
+
+// liquid-rust proprietary
+
+#[derive(Debug, FilterParameters)]
+struct QueryArgs {
+    #[parameter(
+        description = "The JSONPath-like query, like \"$.items[?(@.available)].title\".",
+        arg_type = "str"
+    )]
+    query: Expression,
+}
+
+#[derive(Clone, FilterReflection)]
+#[filter(
+    name = "query",
+    description = "Returns an array of the values nested in the input that match a JSONPath-like query.",
+    parameters(QueryArgs)
+)]
+pub struct Query;
+
+impl ParseFilter for Query {
+    fn parse(&self, arguments: FilterArguments) -> Result<Box<dyn Filter>> {
+        let args = QueryArgs::from_args(arguments)?;
+        // Literal queries are compiled once, so mistakes in them fail parsing
+        let compiled = match &args.query {
+            Expression::Literal(query) if query.type_name() == "string" => {
+                Some(QueryPath::parse(query.to_kstr().as_str())?)
+            }
+            Expression::Literal(_) => {
+                return Err(invalid_argument("query", "String expected"));
+            }
+            Expression::Variable(_) => None,
+        };
+        Ok(Box::new(QueryFilter { args, compiled }))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
+
+#[derive(Debug, Display_filter)]
+#[name = "query"]
+struct QueryFilter {
+    #[parameters]
+    args: QueryArgs,
+    /// The query, when it is a literal.
+    compiled: Option<QueryPath>,
+}
+
+impl Filter for QueryFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> Result<Value> {
+        let parsed;
+        let query = match &self.compiled {
+            Some(query) => query,
+            None => {
+                let args = self.args.evaluate(runtime)?;
+                parsed = QueryPath::parse(args.query.as_str())?;
+                &parsed
+            }
+        };
+
+        let matches = query.select(input).into_iter().map(|v| v.to_value());
+        Ok(Value::Array(matches.collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit_query() {
+        let input = liquid_core::value!({
+            "items": [
+                {"title": "Tea", "available": true},
+                {"title": "Cake", "available": false},
+                {"title": "Coffee", "available": true},
+            ]
+        });
+        assert_eq!(
+            liquid_core::call_filter!(Query, input, "$.items[?(@.available)].title").unwrap(),
+            liquid_core::value!(["Tea", "Coffee"])
+        );
+        assert_eq!(
+            liquid_core::call_filter!(Query, input, "$..missing").unwrap(),
+            liquid_core::value!([])
+        );
+    }
+
+    #[test]
+    fn unit_query_variable() {
+        let input = liquid_core::value!({"a": {"b": 1}, "c": 2});
+        let runtime = liquid_core::runtime::RuntimeBuilder::new().build();
+        runtime.set_global("path".into(), liquid_core::value!("$.a.b"));
+        let args = liquid_core::parser::FilterArguments {
+            positional: Box::new(std::iter::once(Expression::Variable(
+                liquid_core::runtime::Variable::with_literal("path"),
+            ))),
+            keyword: Box::new(std::iter::empty()),
+        };
+        let filter = Query.parse(args).unwrap();
+        assert_eq!(
+            filter.evaluate(&input, &runtime).unwrap(),
+            liquid_core::value!([1])
+        );
+        runtime.set_global("path".into(), liquid_core::value!("c"));
+        assert_eq!(
+            filter.evaluate(&input, &runtime).unwrap(),
+            liquid_core::value!([2])
+        );
+    }
+
+    fn parse_literal(query: Value) -> Result<Box<dyn Filter>> {
+        Query.parse(liquid_core::parser::FilterArguments {
+            positional: Box::new(std::iter::once(Expression::Literal(query))),
+            keyword: Box::new(std::iter::empty()),
+        })
+    }
+
+    #[test]
+    fn unit_query_invalid() {
+        liquid_core::call_filter!(Query, liquid_core::value!({}), "$.items[").unwrap_err();
+        parse_literal(liquid_core::value!("$.items[")).unwrap_err();
+    }
+
+    #[test]
+    fn unit_query_not_a_string() {
+        parse_literal(liquid_core::value!(1)).unwrap_err();
+        parse_literal(liquid_core::value!(["$.a"])).unwrap_err();
+        parse_literal(liquid_core::value!("$.a")).unwrap();
+    }
+}
@@ -3,6 +3,7 @@ use std::io::Write;
 use liquid_core::error::ResultLiquidExt;
 use liquid_core::model::KString;
 use liquid_core::parser::TryMatchToken;
+use liquid_core::runtime::Analyzer;
 use liquid_core::Expression;
 use liquid_core::Language;
 use liquid_core::Renderable;
@@ -33,42 +34,45 @@ impl TagReflection for IncludeTag {
 impl ParseTag for IncludeTag {
     fn parse(
         &self,
-        mut arguments: TagTokenIter<'_>,
+        arguments: TagTokenIter<'_>,
         _options: &Language,
     ) -> Result<Box<dyn Renderable>> {
-        let name = arguments.expect_next("Identifier or literal expected.")?;
+        parse_include(arguments, false)
+    }
 
-        // This may accept strange inputs such as `{% include 0 %}` or `{% include filterchain | filter:0 %}`.
-        // Those inputs would fail anyway by there being not a path with those names so they are not a big concern.
-        let name = match name.expect_identifier() {
-            // Using `to_kstr()` on literals ensures `Strings` will have their quotes trimmed.
-            TryMatchToken::Matches(name) => name.to_kstr().to_string(),
-            TryMatchToken::Fails(name) => name.as_str().to_string(),
-        };
+    fn reflection(&self) -> &dyn TagReflection {
+        self
+    }
+}
 
-        let partial = Expression::with_literal(name);
+/// `{% include_relative path %}` includes a partial-template relative to the current template's
+/// `Runtime::name`, rather than from the top of the partial-templates.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct IncludeRelativeTag;
 
-        let mut vars: Vec<(KString, Expression)> = Vec::new();
-        while let Ok(next) = arguments.expect_next("") {
-            let id = next.expect_identifier().into_result()?.to_string();
+impl IncludeRelativeTag {
+    pub fn new() -> Self {
+        Self
+    }
+}
 
-            arguments
-                .expect_next("\"=\" expected.")?
-                .expect_str("=")
-                .into_result_custom_msg("expected \"=\" to be used for the assignment")?;
-
-            vars.push((
-                id.into(),
-                arguments
-                    .expect_next("expected value")?
-                    .expect_value()
-                    .into_result()?,
-            ));
-        }
+impl TagReflection for IncludeRelativeTag {
+    fn tag(&self) -> &'static str {
+        "include_relative"
+    }
 
-        arguments.expect_nothing()?;
+    fn description(&self) -> &'static str {
+        "Include a partial-template relative to the current template."
+    }
+}
 
-        Ok(Box::new(Include { partial, vars }))
+impl ParseTag for IncludeRelativeTag {
+    fn parse(
+        &self,
+        arguments: TagTokenIter<'_>,
+        _options: &Language,
+    ) -> Result<Box<dyn Renderable>> {
+        parse_include(arguments, true)
     }
 
     fn reflection(&self) -> &dyn TagReflection {
@@ -76,15 +80,82 @@ impl ParseTag for IncludeTag {
     }
 }
 
+fn parse_include(mut arguments: TagTokenIter<'_>, relative: bool) -> Result<Box<dyn Renderable>> {
+    let name = arguments.expect_next("Identifier or literal expected.")?;
+
+    // This may accept strange inputs such as `{% include 0 %}` or `{% include filterchain | filter:0 %}`.
+    // Those inputs would fail anyway by there being not a path with those names so they are not a big concern.
+    let name = match name.expect_identifier() {
+        // Using `to_kstr()` on literals ensures `Strings` will have their quotes trimmed.
+        TryMatchToken::Matches(name) => name.to_kstr().to_string(),
+        TryMatchToken::Fails(name) => match name.expect_literal() {
+            // Quoted, like paths with a `/`
+            TryMatchToken::Matches(name) => name.to_kstr().to_string(),
+            TryMatchToken::Fails(name) => name.as_str().to_string(),
+        },
+    };
+
+    let partial = Expression::with_literal(name);
+
+    let mut vars: Vec<(KString, Expression)> = Vec::new();
+    while let Ok(next) = arguments.expect_next("") {
+        let id = next.expect_identifier().into_result()?.to_string();
+
+        arguments
+            .expect_next("\"=\" expected.")?
+            .expect_str("=")
+            .into_result_custom_msg("expected \"=\" to be used for the assignment")?;
+
+        vars.push((
+            id.into(),
+            arguments
+                .expect_next("expected value")?
+                .expect_value()
+                .into_result()?,
+        ));
+    }
+
+    arguments.expect_nothing()?;
+
+    Ok(Box::new(Include {
+        partial,
+        vars,
+        relative,
+    }))
+}
+
 #[derive(Debug)]
 struct Include {
     partial: Expression,
     vars: Vec<(KString, Expression)>,
+    /// From `include_relative`
+    relative: bool,
+}
+
+impl Include {
+    fn tag(&self) -> &'static str {
+        if self.relative {
+            "include_relative"
+        } else {
+            "include"
+        }
+    }
+
+    fn trace(&self) -> String {
+        format!("{{% {} {} %}}", self.tag(), self.partial)
+    }
 }
 
 impl Renderable for Include {
     fn render_to(&self, writer: &mut dyn Write, runtime: &dyn Runtime) -> Result<()> {
         let name = self.partial.evaluate(runtime)?.render().to_string();
+        let name = if self.relative {
+            let current = runtime.name();
+            relative_name(current.as_ref().map(|n| n.as_str()), &name)
+                .trace_with(|| self.trace().into())?
+        } else {
+            name
+        };
 
         {
             let mut pass_through = std::collections::HashMap::<
@@ -105,21 +176,68 @@ impl Renderable for Include {
                 pass_through.insert("include".into(), &helper_vars);
             }
 
-            let scope = StackFrame::new(runtime, &pass_through);
+            let scope = StackFrame::new(runtime, &pass_through).with_name(KString::from_ref(&name));
             let partial = scope
                 .partials()
                 .get(&name)
-                .trace_with(|| format!("{{% include {} %}}", self.partial).into())?;
+                .trace_with(|| self.trace().into())?;
 
             partial
                 .render_to(writer, &scope)
-                .trace_with(|| format!("{{% include {} %}}", self.partial).into())
+                .trace_with(|| self.trace().into())
                 .context_key_with(|| self.partial.to_string().into())
                 .value_with(|| name.to_string().into())?;
         }
 
         Ok(())
     }
+
+    fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        for (_, val) in &self.vars {
+            val.analyze(analyzer);
+        }
+        if self.relative {
+            // Which partial-template depends on where the template is rendered from
+            return;
+        }
+        let name = match self.partial {
+            Expression::Literal(ref name) => name.to_kstr().into_owned(),
+            Expression::Variable(_) => unreachable!("`include` only accepts literal names"),
+        };
+        if self.vars.is_empty() {
+            analyzer.include_partial(&name, &[]);
+        } else {
+            analyzer.include_partial(&name, &["include".into()]);
+        }
+    }
+}
+
+/// Resolve `path` against the directory of the `current` template, staying within the root.
+fn relative_name(current: Option<&str>, path: &str) -> Result<String> {
+    if path.starts_with('/') {
+        return Error::with_msg("`include_relative` requires a relative path")
+            .context("path", path.to_owned())
+            .into_err();
+    }
+
+    let mut components: Vec<&str> = current.unwrap_or_default().split('/').collect();
+    // Relative to the directory, not the template itself
+    components.pop();
+    components.retain(|c| !c.is_empty());
+    for component in path.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                if components.pop().is_none() {
+                    return Error::with_msg("`include_relative` cannot leave the site root")
+                        .context("path", path.to_owned())
+                        .into_err();
+                }
+            }
+            component => components.push(component),
+        }
+    }
+    Ok(components.join("/"))
 }
 
 #[cfg(test)]
@@ -155,6 +273,9 @@ mod test {
                 "example.txt" => Some(r#"{{'whooo' | size}}{%comment%}What happens{%endcomment%} {%if num < numTwo%}wat{%else%}wot{%endif%} {%if num > numTwo%}wat{%else%}wot{%endif%}"#.into()),
                 "example_var.txt" => Some(r#"{{include.example_var}}"#.into()),
                 "example_multi_var.txt" => Some(r#"{{include.example_var}} {{include.example}}"#.into()),
+                "posts/post.md" => Some(r#"post {% include_relative "snippets/note.md" %}"#.into()),
+                "posts/snippets/note.md" => Some(r#"note {% include_relative "../footer.md" %}"#.into()),
+                "posts/footer.md" => Some(r#"footer"#.into()),
                 _ => None
             }
         }
@@ -165,6 +286,9 @@ mod test {
         options
             .tags
             .register("include".to_string(), IncludeTag.into());
+        options
+            .tags
+            .register("include_relative".to_string(), IncludeRelativeTag.into());
         options
             .blocks
             .register("comment".to_string(), stdlib::CommentBlock.into());
@@ -237,6 +361,24 @@ mod test {
         assert_eq!(output, "hello");
     }
 
+    #[test]
+    fn include_quoted() {
+        let text = "{% include \"example_var.txt\" example_var=\"hello\" %}|{% include 'example_var.txt' example_var=\"world\" %}";
+        let options = options();
+        let template = parser::parse(text, &options)
+            .map(runtime::Template::new)
+            .unwrap();
+
+        let partials = partials::OnDemandCompiler::<TestSource>::empty()
+            .compile(::std::sync::Arc::new(options))
+            .unwrap();
+        let runtime = RuntimeBuilder::new()
+            .set_partials(partials.as_ref())
+            .build();
+        let output = template.render(&runtime).unwrap();
+        assert_eq!(output, "hello|world");
+    }
+
     #[test]
     fn include_multiple_variable() {
         let text = "{% include example_multi_var.txt example_var=\"hello\" example=\"world\" %}";
@@ -277,4 +419,60 @@ mod test {
         let output = template.render(&runtime);
         assert!(output.is_err());
     }
+
+    #[test]
+    fn include_relative() {
+        let text = "{% include 'posts/post.md' %}";
+        let options = options();
+        let template = parser::parse(text, &options)
+            .map(runtime::Template::new)
+            .unwrap();
+
+        let partials = partials::OnDemandCompiler::<TestSource>::empty()
+            .compile(::std::sync::Arc::new(options))
+            .unwrap();
+        let runtime = RuntimeBuilder::new()
+            .set_partials(partials.as_ref())
+            .build();
+        let output = template.render(&runtime).unwrap();
+        assert_eq!(output, "post note footer");
+    }
+
+    #[test]
+    fn include_relative_to_name() {
+        let text = "{% include_relative '../footer.md' %}";
+        let options = options();
+        let template = parser::parse(text, &options)
+            .map(runtime::Template::new)
+            .unwrap();
+
+        let partials = partials::OnDemandCompiler::<TestSource>::empty()
+            .compile(::std::sync::Arc::new(options))
+            .unwrap();
+        let runtime = RuntimeBuilder::new()
+            .set_partials(partials.as_ref())
+            .set_name("posts/drafts/draft.md")
+            .build();
+        let output = template.render(&runtime).unwrap();
+        assert_eq!(output, "footer");
+    }
+
+    #[test]
+    fn include_relative_outside_root() {
+        let text = "{% include_relative '../../footer.md' %}";
+        let options = options();
+        let template = parser::parse(text, &options)
+            .map(runtime::Template::new)
+            .unwrap();
+
+        let partials = partials::OnDemandCompiler::<TestSource>::empty()
+            .compile(::std::sync::Arc::new(options))
+            .unwrap();
+        let runtime = RuntimeBuilder::new()
+            .set_partials(partials.as_ref())
+            .set_name("posts/post.md")
+            .build();
+        let output = template.render(&runtime);
+        assert!(output.is_err());
+    }
 }
@@ -1,7 +1,8 @@
 use std::io::Write;
 
 use liquid_core::error::ResultLiquidExt;
-use liquid_core::model::Value;
+use liquid_core::model::{ScalarCow, Value};
+use liquid_core::runtime::Analyzer;
 use liquid_core::Language;
 use liquid_core::Renderable;
 use liquid_core::Result;
@@ -56,7 +57,11 @@ impl ParseBlock for CaptureBlock {
         );
 
         tokens.assert_empty();
-        Ok(Box::new(Capture { id, template }))
+        Ok(Box::new(Capture {
+            id,
+            template,
+            safe: options.auto_escape,
+        }))
     }
 
     fn reflection(&self) -> &dyn BlockReflection {
@@ -68,6 +73,8 @@ impl ParseBlock for CaptureBlock {
 struct Capture {
     id: liquid_core::model::KString,
     template: Template,
+    /// The captured output was already escaped.
+    safe: bool,
 }
 
 impl Capture {
@@ -84,9 +91,19 @@ impl Renderable for Capture {
             .trace_with(|| self.trace().into())?;
 
         let output = String::from_utf8(captured).expect("render only writes UTF-8");
-        runtime.set_global(self.id.clone(), Value::scalar(output));
+        let output = if self.safe {
+            ScalarCow::safe(output)
+        } else {
+            ScalarCow::new(output)
+        };
+        runtime.set_global(self.id.clone(), Value::Scalar(output));
         Ok(())
     }
+
+    fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        self.template.analyze(analyzer);
+        analyzer.set_global(self.id.clone());
+    }
 }
 
 #[cfg(test)]
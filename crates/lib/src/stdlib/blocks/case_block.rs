@@ -4,6 +4,7 @@ use liquid_core::error::ResultLiquidExt;
 use liquid_core::model::{ValueView, ValueViewCmp};
 use liquid_core::parser::BlockElement;
 use liquid_core::parser::TryMatchToken;
+use liquid_core::runtime::Analyzer;
 use liquid_core::Expression;
 use liquid_core::Language;
 use liquid_core::Renderable;
@@ -163,6 +164,19 @@ impl Renderable for Case {
 
         Ok(())
     }
+
+    fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        self.target.analyze(analyzer);
+        for case in &self.cases {
+            for arg in &case.args {
+                arg.analyze(analyzer);
+            }
+            case.template.analyze(analyzer);
+        }
+        if let Some(ref t) = self.else_block {
+            t.analyze(analyzer);
+        }
+    }
 }
 
 #[derive(Debug)]
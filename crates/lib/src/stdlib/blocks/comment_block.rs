@@ -1,6 +1,7 @@
 use std::io::Write;
 
 use liquid_core::parser::BlockElement;
+use liquid_core::runtime::Analyzer;
 use liquid_core::Language;
 use liquid_core::Renderable;
 use liquid_core::Result;
@@ -70,6 +71,8 @@ impl Renderable for Comment {
     fn render_to(&self, _writer: &mut dyn Write, _runtime: &dyn Runtime) -> Result<()> {
         Ok(())
     }
+
+    fn analyze(&self, _analyzer: &mut Analyzer<'_>) {}
 }
 
 #[cfg(test)]
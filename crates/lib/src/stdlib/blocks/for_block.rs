@@ -4,8 +4,8 @@ use std::io::Write;
 use liquid_core::error::{ResultLiquidExt, ResultLiquidReplaceExt};
 use liquid_core::model::{Object, ObjectView, Value, ValueCow, ValueView};
 use liquid_core::parser::BlockElement;
-use liquid_core::parser::TryMatchToken;
-use liquid_core::runtime::{Interrupt, InterruptRegister};
+use liquid_core::parser::{TagToken, TryMatchToken};
+use liquid_core::runtime::{Analyzer, Interrupt, InterruptRegister};
 use liquid_core::Expression;
 use liquid_core::Language;
 use liquid_core::Renderable;
@@ -49,8 +49,9 @@ impl ParseBlock for ForBlock {
             .expect_identifier()
             .into_result()?;
 
-        arguments
-            .expect_next("\"in\" expected.")?
+        let token = arguments.expect_next("\"in\" expected.")?;
+        let (value_name, token) = parse_value_name(token, &mut arguments)?;
+        token
             .expect_str("in")
             .into_result_custom_msg("\"in\" expected.")?;
 
@@ -71,7 +72,7 @@ impl ParseBlock for ForBlock {
         while let Some(token) = arguments.next() {
             match token.as_str() {
                 "limit" => limit = Some(parse_attr(&mut arguments)?),
-                "offset" => offset = Some(parse_attr(&mut arguments)?),
+                "offset" => offset = Some(parse_offset(&mut arguments)?),
                 "reversed" => reversed = true,
                 _ => {
                     return token
@@ -107,7 +108,9 @@ impl ParseBlock for ForBlock {
 
         tokens.assert_empty();
         Ok(Box::new(For {
+            name: format!("{}-{}", var_name, range),
             var_name: liquid_core::model::KString::from_ref(var_name),
+            value_name,
             range,
             item_template,
             else_template,
@@ -124,19 +127,27 @@ impl ParseBlock for ForBlock {
 
 #[derive(Debug)]
 struct For {
+    /// Identifies the loop in `ForRegister`, for `offset: continue`.
+    name: String,
     var_name: liquid_core::model::KString,
+    /// The second name of `{% for key, value in object %}`.
+    value_name: Option<liquid_core::model::KString>,
     range: RangeExpression,
     item_template: Template,
     else_template: Option<Template>,
     limit: Option<Expression>,
-    offset: Option<Expression>,
+    offset: Option<LoopOffset>,
     reversed: bool,
 }
 
 impl For {
     fn trace(&self) -> String {
+        let var_name = match &self.value_name {
+            Some(value_name) => format!("{}, {}", self.var_name, value_name),
+            None => self.var_name.to_string(),
+        };
         trace_for_tag(
-            self.var_name.as_str(),
+            &var_name,
             &self.range,
             &self.limit,
             &self.offset,
@@ -149,7 +160,7 @@ fn trace_for_tag(
     var_name: &str,
     range: &RangeExpression,
     limit: &Option<Expression>,
-    offset: &Option<Expression>,
+    offset: &Option<LoopOffset>,
     reversed: bool,
 ) -> String {
     let mut parameters = vec![];
@@ -178,8 +189,9 @@ impl Renderable for For {
             .trace_with(|| self.trace().into())?;
         let array = range.evaluate()?;
         let limit = evaluate_attr(&self.limit, runtime)?;
-        let offset = evaluate_attr(&self.offset, runtime)?.unwrap_or(0);
+        let offset = evaluate_offset(&self.offset, &self.name, runtime)?;
         let array = iter_array(array, limit, offset, self.reversed);
+        save_offset(&self.name, offset + array.len(), runtime);
 
         match array.len() {
             0 => {
@@ -200,7 +212,24 @@ impl Renderable for For {
                         &dyn ValueView,
                     >::new();
                     root.insert("forloop".into(), &forloop);
-                    root.insert(self.var_name.as_ref(), &v);
+                    match &self.value_name {
+                        Some(value_name) => {
+                            // Objects are iterated as `[key, value]` pairs.
+                            let (key, value) = v
+                                .as_array()
+                                .filter(|pair| pair.size() == 2)
+                                .and_then(|pair| Some((pair.get(0)?, pair.get(1)?)))
+                                .ok_or_else(|| {
+                                    unexpected_value_error("`[key, value]` pair", Some(v.source()))
+                                })
+                                .trace_with(|| self.trace().into())?;
+                            root.insert(self.var_name.as_ref(), key);
+                            root.insert(value_name.as_ref(), value);
+                        }
+                        None => {
+                            root.insert(self.var_name.as_ref(), &v);
+                        }
+                    }
 
                     let scope = StackFrame::new(runtime, &root);
                     self.item_template
@@ -223,6 +252,28 @@ impl Renderable for For {
         }
         Ok(())
     }
+
+    fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        self.range.analyze(analyzer);
+        analyze_attrs(
+            &[
+                self.limit.as_ref(),
+                self.offset.as_ref().and_then(LoopOffset::expression),
+            ],
+            analyzer,
+        );
+        analyzer.push_scope();
+        analyzer.declare("forloop".into());
+        analyzer.declare(self.var_name.clone());
+        if let Some(value_name) = &self.value_name {
+            analyzer.declare(value_name.clone());
+        }
+        self.item_template.analyze(analyzer);
+        analyzer.pop_scope();
+        if let Some(ref t) = self.else_template {
+            t.analyze(analyzer);
+        }
+    }
 }
 
 #[derive(Debug, Clone, ValueView, ObjectView)]
@@ -319,7 +370,7 @@ impl ParseBlock for TableRowBlock {
             match token.as_str() {
                 "cols" => cols = Some(parse_attr(&mut arguments)?),
                 "limit" => limit = Some(parse_attr(&mut arguments)?),
-                "offset" => offset = Some(parse_attr(&mut arguments)?),
+                "offset" => offset = Some(parse_offset(&mut arguments)?),
                 _ => {
                     return token
                         .raise_custom_error("\"cols\", \"limit\" or \"offset\" expected.")
@@ -335,6 +386,7 @@ impl ParseBlock for TableRowBlock {
 
         tokens.assert_empty();
         Ok(Box::new(TableRow {
+            name: format!("{}-{}", var_name, range),
             var_name: liquid_core::model::KString::from_ref(var_name),
             range,
             item_template,
@@ -351,12 +403,14 @@ impl ParseBlock for TableRowBlock {
 
 #[derive(Debug)]
 struct TableRow {
+    /// Identifies the loop in `ForRegister`, for `offset: continue`.
+    name: String,
     var_name: liquid_core::model::KString,
     range: RangeExpression,
     item_template: Template,
     cols: Option<Expression>,
     limit: Option<Expression>,
-    offset: Option<Expression>,
+    offset: Option<LoopOffset>,
 }
 
 impl TableRow {
@@ -376,7 +430,7 @@ fn trace_tablerow_tag(
     range: &RangeExpression,
     cols: &Option<Expression>,
     limit: &Option<Expression>,
-    offset: &Option<Expression>,
+    offset: &Option<LoopOffset>,
 ) -> String {
     let mut parameters = vec![];
     if let Some(cols) = cols {
@@ -405,8 +459,9 @@ impl Renderable for TableRow {
         let array = range.evaluate()?;
         let cols = evaluate_attr(&self.cols, runtime)?;
         let limit = evaluate_attr(&self.limit, runtime)?;
-        let offset = evaluate_attr(&self.offset, runtime)?.unwrap_or(0);
+        let offset = evaluate_offset(&self.offset, &self.name, runtime)?;
         let array = iter_array(array, limit, offset, false);
+        save_offset(&self.name, offset + array.len(), runtime);
 
         let mut helper_vars = Object::new();
 
@@ -447,6 +502,23 @@ impl Renderable for TableRow {
 
         Ok(())
     }
+
+    fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        self.range.analyze(analyzer);
+        analyze_attrs(
+            &[
+                self.cols.as_ref(),
+                self.limit.as_ref(),
+                self.offset.as_ref().and_then(LoopOffset::expression),
+            ],
+            analyzer,
+        );
+        analyzer.push_scope();
+        analyzer.declare("tablerow".into());
+        analyzer.declare(self.var_name.clone());
+        self.item_template.analyze(analyzer);
+        analyzer.pop_scope();
+    }
 }
 
 #[derive(Debug, Clone, ValueView, ObjectView)]
@@ -490,6 +562,35 @@ impl TableRowObject {
     }
 }
 
+/// Parses the `, value` of `{% for key, value in object %}`, returning the token after it.
+#[cfg(feature = "extra")]
+fn parse_value_name<'a>(
+    token: TagToken<'a>,
+    arguments: &mut TagTokenIter<'a>,
+) -> Result<(Option<liquid_core::model::KString>, TagToken<'a>)> {
+    if token.as_str() != "," {
+        return Ok((None, token));
+    }
+    let value_name = arguments
+        .expect_next("Identifier expected.")?
+        .expect_identifier()
+        .into_result()?;
+    let token = arguments.expect_next("\"in\" expected.")?;
+    Ok((
+        Some(liquid_core::model::KString::from_ref(value_name)),
+        token,
+    ))
+}
+
+/// `{% for key, value in object %}` is only available with the `extra` feature.
+#[cfg(not(feature = "extra"))]
+fn parse_value_name<'a>(
+    token: TagToken<'a>,
+    _arguments: &mut TagTokenIter<'a>,
+) -> Result<(Option<liquid_core::model::KString>, TagToken<'a>)> {
+    Ok((None, token))
+}
+
 /// Extracts an integer value or an identifier from the token stream
 fn parse_attr(arguments: &mut TagTokenIter<'_>) -> Result<Expression> {
     arguments
@@ -506,19 +607,105 @@ fn parse_attr(arguments: &mut TagTokenIter<'_>) -> Result<Expression> {
 /// Evaluates an attribute, returning Ok(None) if input is also None.
 fn evaluate_attr(attr: &Option<Expression>, runtime: &dyn Runtime) -> Result<Option<usize>> {
     match attr {
-        Some(attr) => {
-            let value = attr.evaluate(runtime)?;
-            let value = value
-                .as_scalar()
-                .and_then(|s| s.to_integer())
-                .ok_or_else(|| unexpected_value_error("whole number", Some(value.type_name())))?
-                as usize;
-            Ok(Some(value))
-        }
+        Some(attr) => evaluate_integer(attr, runtime).map(Some),
         None => Ok(None),
     }
 }
 
+fn evaluate_integer(attr: &Expression, runtime: &dyn Runtime) -> Result<usize> {
+    let value = attr.evaluate(runtime)?;
+    let value = value
+        .as_scalar()
+        .and_then(|s| s.to_integer())
+        .ok_or_else(|| unexpected_value_error("whole number", Some(value.type_name())))?
+        as usize;
+    Ok(value)
+}
+
+/// Reports the variables the attributes depend on.
+fn analyze_attrs(attrs: &[Option<&Expression>], analyzer: &mut Analyzer<'_>) {
+    for attr in attrs.iter().copied().flatten() {
+        attr.analyze(analyzer);
+    }
+}
+
+/// The `offset` of a loop.
+#[derive(Clone, Debug)]
+enum LoopOffset {
+    Expression(Expression),
+    /// Resume where the last loop over the same collection, with the same variable, stopped.
+    Continue,
+}
+
+impl LoopOffset {
+    fn expression(&self) -> Option<&Expression> {
+        match self {
+            LoopOffset::Expression(expr) => Some(expr),
+            LoopOffset::Continue => None,
+        }
+    }
+}
+
+impl fmt::Display for LoopOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoopOffset::Expression(expr) => write!(f, "{}", expr),
+            LoopOffset::Continue => write!(f, "continue"),
+        }
+    }
+}
+
+/// Extracts an `offset` value, which may be `continue`, from the token stream
+fn parse_offset(arguments: &mut TagTokenIter<'_>) -> Result<LoopOffset> {
+    arguments
+        .expect_next("\":\" expected.")?
+        .expect_str(":")
+        .into_result_custom_msg("\":\" expected.")?;
+
+    let token = arguments.expect_next("Value expected.")?;
+    if token.as_str() == "continue" {
+        return Ok(LoopOffset::Continue);
+    }
+    token
+        .expect_value()
+        .into_result()
+        .map(LoopOffset::Expression)
+}
+
+/// Evaluates the offset of the loop called `name`, defaulting to 0.
+fn evaluate_offset(
+    offset: &Option<LoopOffset>,
+    name: &str,
+    runtime: &dyn Runtime,
+) -> Result<usize> {
+    match offset {
+        Some(LoopOffset::Expression(expr)) => evaluate_integer(expr, runtime),
+        Some(LoopOffset::Continue) => Ok(runtime
+            .registers()
+            .get_mut::<ForRegister>()
+            .offsets
+            .get(name)
+            .copied()
+            .unwrap_or(0)),
+        None => Ok(0),
+    }
+}
+
+/// Records where the loop called `name` stopped, for a later `offset: continue`.
+fn save_offset(name: &str, offset: usize, runtime: &dyn Runtime) {
+    runtime
+        .registers()
+        .get_mut::<ForRegister>()
+        .offsets
+        .insert(name.to_owned(), offset);
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+struct ForRegister {
+    // Where each loop, named by its variable and collection, stopped.
+    offsets: std::collections::HashMap<String, usize>,
+}
+
 #[derive(Clone, Debug)]
 pub enum RangeExpression {
     Array(Expression),
@@ -542,6 +729,16 @@ impl RangeExpression {
 
         Ok(range)
     }
+
+    pub fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        match *self {
+            RangeExpression::Array(ref array_id) => array_id.analyze(analyzer),
+            RangeExpression::Counted(ref start_arg, ref stop_arg) => {
+                start_arg.analyze(analyzer);
+                stop_arg.analyze(analyzer);
+            }
+        }
+    }
 }
 
 impl fmt::Display for RangeExpression {
@@ -615,7 +812,7 @@ fn iter_array(
 ) -> Vec<ValueCow<'_>> {
     let offset = ::std::cmp::min(offset, range.len());
     let limit = limit
-        .map(|l| ::std::cmp::min(l, range.len()))
+        .map(|l| ::std::cmp::min(l, range.len() - offset))
         .unwrap_or_else(|| range.len() - offset);
     range.drain(0..offset);
     range.resize(limit, Value::Nil.into());
@@ -925,6 +1122,136 @@ mod test {
         assert_eq!(output, "1 2 3 4 5 ");
     }
 
+    #[test]
+    fn offset_and_limit_past_end() {
+        let text = concat!(
+            "{% for i in (1..5) offset:3 limit:10 %}",
+            "{{ i }} ",
+            "{% endfor %}"
+        );
+        let template = parser::parse(text, &options())
+            .map(runtime::Template::new)
+            .unwrap();
+
+        let runtime = RuntimeBuilder::new().build();
+        let output = template.render(&runtime).unwrap();
+        assert_eq!(output, "4 5 ");
+    }
+
+    #[test]
+    fn offset_continue_loop() {
+        let text = concat!(
+            "{% for i in array limit:3 %}{{ i }}{% endfor %}|",
+            "{% for i in array offset:continue limit:3 %}{{ i }}{% endfor %}|",
+            "{% for i in array offset:continue %}{{ i }}{% endfor %}|",
+            "{% for i in array offset:continue %}{{ i }}{% endfor %}|",
+            "{% for j in array offset:continue limit:2 %}{{ j }}{% endfor %}",
+        );
+        let template = parser::parse(text, &options())
+            .map(runtime::Template::new)
+            .unwrap();
+
+        let runtime = RuntimeBuilder::new().build();
+        runtime.set_global(
+            "array".into(),
+            Value::Array((1..=7).map(Value::scalar).collect()),
+        );
+        let output = template.render(&runtime).unwrap();
+        assert_eq!(output, "123|456|7||12");
+    }
+
+    #[test]
+    fn tablerow_offset_continue() {
+        let text = concat!(
+            "{% for i in array limit:2 %}{{ i }}{% endfor %}",
+            "{% tablerow i in array offset:continue limit:2 %}{{ i }}{% endtablerow %}",
+            "{% tablerow i in array offset:continue %}{{ i }}{% endtablerow %}",
+        );
+        let template = parser::parse(text, &options())
+            .map(runtime::Template::new)
+            .unwrap();
+
+        let runtime = RuntimeBuilder::new().build();
+        runtime.set_global(
+            "array".into(),
+            Value::Array((1..=5).map(Value::scalar).collect()),
+        );
+        let output = template.render(&runtime).unwrap();
+        assert_eq!(
+            output,
+            concat!(
+                "12",
+                "<tr class=\"row1\"><td class=\"col1\">3</td><td class=\"col2\">4</td></tr>",
+                "<tr class=\"row1\"><td class=\"col1\">5</td></tr>",
+            )
+        );
+    }
+
+    /// Iterates in insertion order, since it needs `preserve_order`.
+    #[cfg(feature = "preserve_order")]
+    fn settings() -> Value {
+        let settings: Object = [("a", 1), ("b", 2), ("c", 3), ("d", 4)]
+            .into_iter()
+            .map(|(k, v)| (k.into(), Value::scalar(v)))
+            .collect();
+        Value::Object(settings)
+    }
+
+    #[test]
+    #[cfg(feature = "preserve_order")]
+    fn loop_over_object_pairs() {
+        let text = concat!(
+            "{% for pair in settings offset:1 limit:2 reversed %}",
+            "{{ pair[0] }}={{ pair[1] }} ",
+            "{% endfor %}"
+        );
+        let template = parser::parse(text, &options())
+            .map(runtime::Template::new)
+            .unwrap();
+
+        let runtime = RuntimeBuilder::new().build();
+        runtime.set_global("settings".into(), settings());
+        let output = template.render(&runtime).unwrap();
+        assert_eq!(output, "c=3 b=2 ");
+    }
+
+    #[test]
+    #[cfg(all(feature = "extra", feature = "preserve_order"))]
+    fn loop_over_object_keys_and_values() {
+        let text = concat!(
+            "{% for key, value in settings %}",
+            "{{ key }}={{ value }} ",
+            "{% endfor %}"
+        );
+        let template = parser::parse(text, &options())
+            .map(runtime::Template::new)
+            .unwrap();
+
+        let runtime = RuntimeBuilder::new().build();
+        runtime.set_global("settings".into(), settings());
+        let output = template.render(&runtime).unwrap();
+        assert_eq!(output, "a=1 b=2 c=3 d=4 ");
+    }
+
+    #[test]
+    #[cfg(feature = "extra")]
+    fn loop_over_non_pairs_with_keys_and_values() {
+        let text = "{% for key, value in (1..3) %}{{ key }}{% endfor %}";
+        let template = parser::parse(text, &options())
+            .map(runtime::Template::new)
+            .unwrap();
+
+        let runtime = RuntimeBuilder::new().build();
+        template.render(&runtime).unwrap_err();
+    }
+
+    #[test]
+    #[cfg(not(feature = "extra"))]
+    fn loop_over_object_keys_and_values_requires_extra() {
+        let text = "{% for key, value in items %}{% endfor %}";
+        assert!(parser::parse(text, &options()).is_err());
+    }
+
     #[test]
     fn loop_variables() {
         let text = concat!(
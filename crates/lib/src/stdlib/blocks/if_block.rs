@@ -5,6 +5,7 @@ use liquid_core::error::ResultLiquidExt;
 use liquid_core::model::{ValueView, ValueViewCmp};
 use liquid_core::parser::BlockElement;
 use liquid_core::parser::TagToken;
+use liquid_core::runtime::Analyzer;
 use liquid_core::Expression;
 use liquid_core::Language;
 use liquid_core::Renderable;
@@ -193,6 +194,14 @@ impl Renderable for Conditional {
 
         Ok(())
     }
+
+    fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        self.condition.analyze(analyzer);
+        self.if_true.analyze(analyzer);
+        if let Some(ref template) = self.if_false {
+            template.analyze(analyzer);
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -216,6 +225,21 @@ impl Condition {
             }
         }
     }
+
+    fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        match *self {
+            Condition::Binary(ref c) => {
+                c.lh.analyze(analyzer);
+                c.rh.analyze(analyzer);
+            }
+            Condition::Existence(ref c) => c.lh.analyze(analyzer),
+            Condition::Conjunction(ref left, ref right)
+            | Condition::Disjunction(ref left, ref right) => {
+                left.analyze(analyzer);
+                right.analyze(analyzer);
+            }
+        }
+    }
 }
 
 impl fmt::Display for Condition {
@@ -242,15 +266,24 @@ impl BinaryCondition {
         let ca = ValueViewCmp::new(a.as_view());
         let b = self.rh.evaluate(runtime)?;
         let cb = ValueViewCmp::new(b.as_view());
-
-        let result = match self.comparison {
-            ComparisonOperator::Equals => ca == cb,
-            ComparisonOperator::NotEquals => ca != cb,
-            ComparisonOperator::LessThan => ca < cb,
-            ComparisonOperator::GreaterThan => ca > cb,
-            ComparisonOperator::LessThanEquals => ca <= cb,
-            ComparisonOperator::GreaterThanEquals => ca >= cb,
-            ComparisonOperator::Contains => contains_check(a.as_view(), b.as_view())?,
+        let collated = runtime
+            .collation()
+            .and_then(|collation| collation.compare_values(a.as_view(), b.as_view()));
+
+        let result = match (&self.comparison, collated) {
+            (ComparisonOperator::Equals, Some(ordering)) => ordering.is_eq(),
+            (ComparisonOperator::NotEquals, Some(ordering)) => ordering.is_ne(),
+            (ComparisonOperator::LessThan, Some(ordering)) => ordering.is_lt(),
+            (ComparisonOperator::GreaterThan, Some(ordering)) => ordering.is_gt(),
+            (ComparisonOperator::LessThanEquals, Some(ordering)) => ordering.is_le(),
+            (ComparisonOperator::GreaterThanEquals, Some(ordering)) => ordering.is_ge(),
+            (ComparisonOperator::Equals, _) => ca == cb,
+            (ComparisonOperator::NotEquals, _) => ca != cb,
+            (ComparisonOperator::LessThan, _) => ca < cb,
+            (ComparisonOperator::GreaterThan, _) => ca > cb,
+            (ComparisonOperator::LessThanEquals, _) => ca <= cb,
+            (ComparisonOperator::GreaterThanEquals, _) => ca >= cb,
+            (ComparisonOperator::Contains, _) => contains_check(a.as_view(), b.as_view())?,
         };
 
         Ok(result)
@@ -1,6 +1,7 @@
 use std::io::Write;
 
 use liquid_core::error::{ResultLiquidExt, ResultLiquidReplaceExt};
+use liquid_core::runtime::Analyzer;
 use liquid_core::Language;
 use liquid_core::Renderable;
 use liquid_core::Result;
@@ -81,6 +82,10 @@ impl Renderable for IfChanged {
 
         Ok(())
     }
+
+    fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        self.if_changed.analyze(analyzer);
+    }
 }
 
 /// Remembers the content of the last rendered `ifstate` block.
@@ -1,6 +1,7 @@
 use std::io::Write;
 
 use liquid_core::error::ResultLiquidReplaceExt;
+use liquid_core::runtime::Analyzer;
 use liquid_core::Language;
 use liquid_core::Renderable;
 use liquid_core::Result;
@@ -61,6 +62,8 @@ impl Renderable for RawT {
         write!(writer, "{}", self.content).replace("Failed to render")?;
         Ok(())
     }
+
+    fn analyze(&self, _analyzer: &mut Analyzer<'_>) {}
 }
 
 #[cfg(test)]
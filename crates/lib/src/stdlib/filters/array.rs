@@ -1,6 +1,6 @@
 use std::cmp;
 
-use liquid_core::model::ValueViewCmp;
+use liquid_core::model::{Collation, KStringCow, ValueViewCmp};
 use liquid_core::Expression;
 use liquid_core::Result;
 use liquid_core::Runtime;
@@ -61,32 +61,71 @@ impl Filter for JoinFilter {
     }
 }
 
-fn nil_safe_compare(a: &dyn ValueView, b: &dyn ValueView) -> Option<cmp::Ordering> {
+/// Look up a literal `locale` argument once, when the filter is parsed.
+fn literal_collation(locale: &Option<Expression>) -> Option<Collation> {
+    match locale {
+        Some(Expression::Literal(locale)) => Collation::new(locale.to_kstr().as_str()),
+        _ => None,
+    }
+}
+
+/// The collation named by the `locale` argument, else the runtime's.
+fn collation(
+    literal: Option<&Collation>,
+    locale: Option<KStringCow<'_>>,
+    runtime: &dyn Runtime,
+) -> Result<Option<Collation>> {
+    match (literal, locale) {
+        (Some(literal), _) => Ok(Some(literal.clone())),
+        (None, Some(locale)) => Collation::new(locale.as_str())
+            .map(Some)
+            .ok_or_else(|| invalid_argument("locale", "Unknown or unsupported locale")),
+        (None, None) => Ok(runtime.collation().cloned()),
+    }
+}
+
+fn nil_safe_compare(
+    a: &dyn ValueView,
+    b: &dyn ValueView,
+    collation: Option<&Collation>,
+) -> Option<cmp::Ordering> {
     if a.is_nil() && b.is_nil() {
         Some(cmp::Ordering::Equal)
     } else if a.is_nil() {
         Some(cmp::Ordering::Greater)
     } else if b.is_nil() {
         Some(cmp::Ordering::Less)
+    } else if let Some(ordering) = collation.and_then(|c| c.compare_values(a, b)) {
+        Some(ordering)
     } else {
         ValueViewCmp::new(a).partial_cmp(&ValueViewCmp::new(b))
     }
 }
 
-fn nil_safe_casecmp_key(value: &dyn ValueView) -> Option<String> {
+fn nil_safe_casecmp_key(value: &dyn ValueView, collation: Option<&Collation>) -> Option<String> {
     if value.is_nil() {
         None
+    } else if collation.is_some() {
+        // The collation ignores case itself
+        Some(value.to_kstr().to_string())
     } else {
         Some(value.to_kstr().to_lowercase())
     }
 }
 
-fn nil_safe_casecmp(a: &Option<String>, b: &Option<String>) -> Option<cmp::Ordering> {
+fn nil_safe_casecmp(
+    a: &Option<String>,
+    b: &Option<String>,
+    collation: Option<&Collation>,
+) -> Option<cmp::Ordering> {
     match (a, b) {
         (None, None) => Some(cmp::Ordering::Equal),
         (None, _) => Some(cmp::Ordering::Greater),
         (_, None) => Some(cmp::Ordering::Less),
-        (a, b) => a.partial_cmp(b),
+        (Some(a), Some(b)) => match collation {
+            Some(collation) => Some(collation.compare_ignore_case(a, b)),
+            None => a.partial_cmp(b),
+        },
     }
 }
 
@@ -94,6 +133,12 @@ fn nil_safe_casecmp(a: &Option<String>, b: &Option<String>) -> Option<cmp::Order
 struct PropertyArgs {
     #[parameter(description = "The property accessed by the filter.", arg_type = "str")]
     property: Option<Expression>,
+    #[parameter(
+        description = "The locale to order strings by, like `de`.",
+        arg_type = "str",
+        mode = "keyword"
+    )]
+    locale: Option<Expression>,
 }
 
 #[derive(Clone, ParseFilter, FilterReflection)]
@@ -105,11 +150,19 @@ struct PropertyArgs {
 )]
 pub struct Sort;
 
-#[derive(Debug, Default, FromFilterParameters, Display_filter)]
+#[derive(Debug, Default, Display_filter)]
 #[name = "sort"]
 struct SortFilter {
     #[parameters]
     args: PropertyArgs,
+    collation: Option<Collation>,
+}
+
+impl From<PropertyArgs> for SortFilter {
+    fn from(args: PropertyArgs) -> Self {
+        let collation = literal_collation(&args.locale);
+        Self { args, collation }
+    }
 }
 
 fn safe_property_getter<'a>(value: &'a Value, property: &str) -> &'a dyn ValueView {
@@ -122,6 +175,8 @@ fn safe_property_getter<'a>(value: &'a Value, property: &str) -> &'a dyn ValueVi
 impl Filter for SortFilter {
     fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> Result<Value> {
         let args = self.args.evaluate(runtime)?;
+        let collation = collation(self.collation.as_ref(), args.locale, runtime)?;
+        let collation = collation.as_ref();
 
         let input: Vec<_> = as_sequence(input).collect();
         if args.property.is_some() && !input.iter().all(|v| v.is_object()) {
@@ -135,11 +190,13 @@ impl Filter for SortFilter {
                 nil_safe_compare(
                     safe_property_getter(a, property),
                     safe_property_getter(b, property),
+                    collation,
                 )
                 .unwrap_or(cmp::Ordering::Equal)
             });
         } else {
-            sorted.sort_by(|a, b| nil_safe_compare(a, b).unwrap_or(cmp::Ordering::Equal));
+            sorted
+                .sort_by(|a, b| nil_safe_compare(a, b, collation).unwrap_or(cmp::Ordering::Equal));
         }
         Ok(Value::array(sorted))
     }
@@ -154,16 +211,26 @@ impl Filter for SortFilter {
 )]
 pub struct SortNatural;
 
-#[derive(Debug, Default, FromFilterParameters, Display_filter)]
+#[derive(Debug, Default, Display_filter)]
 #[name = "sort_natural"]
 struct SortNaturalFilter {
     #[parameters]
     args: PropertyArgs,
+    collation: Option<Collation>,
+}
+
+impl From<PropertyArgs> for SortNaturalFilter {
+    fn from(args: PropertyArgs) -> Self {
+        let collation = literal_collation(&args.locale);
+        Self { args, collation }
+    }
 }
 
 impl Filter for SortNaturalFilter {
     fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> Result<Value> {
         let args = self.args.evaluate(runtime)?;
+        let collation = collation(self.collation.as_ref(), args.locale, runtime)?;
+        let collation = collation.as_ref();
 
         let input: Vec<_> = as_sequence(input).collect();
         if args.property.is_some() && !input.iter().all(|v| v.is_object()) {
@@ -176,7 +243,10 @@ impl Filter for SortNaturalFilter {
                 .map(|v| v.to_value())
                 .map(|v| {
                     (
-                        nil_safe_casecmp_key(&safe_property_getter(&v, property).to_value()),
+                        nil_safe_casecmp_key(
+                            &safe_property_getter(&v, property).to_value(),
+                            collation,
+                        ),
                         v,
                     )
                 })
@@ -185,10 +255,12 @@ impl Filter for SortNaturalFilter {
             input
                 .iter()
                 .map(|v| v.to_value())
-                .map(|v| (nil_safe_casecmp_key(&v), v))
+                .map(|v| (nil_safe_casecmp_key(&v, collation), v))
                 .collect()
         };
-        sorted.sort_by(|a, b| nil_safe_casecmp(&a.0, &b.0).unwrap_or(cmp::Ordering::Equal));
+        sorted.sort_by(|a, b| {
+            nil_safe_casecmp(&a.0, &b.0, collation).unwrap_or(cmp::Ordering::Equal)
+        });
         let result: Vec<_> = sorted.into_iter().map(|(_, v)| v).collect();
         Ok(Value::array(result))
     }
@@ -268,6 +340,16 @@ impl Filter for WhereFilter {
     }
 }
 
+#[derive(Debug, Default, FilterParameters)]
+struct UniqArgs {
+    #[parameter(
+        description = "The locale to compare strings by, like `de`.",
+        arg_type = "str",
+        mode = "keyword"
+    )]
+    locale: Option<Expression>,
+}
+
 /// Removes any duplicate elements in an array.
 ///
 /// This has an O(n^2) worst-case complexity.
@@ -275,27 +357,46 @@ impl Filter for WhereFilter {
 #[filter(
     name = "uniq",
     description = "Removes any duplicate elements in an array.",
+    parameters(UniqArgs),
     parsed(UniqFilter)
 )]
 pub struct Uniq;
 
 #[derive(Debug, Default, Display_filter)]
 #[name = "uniq"]
-struct UniqFilter;
+struct UniqFilter {
+    #[parameters]
+    args: UniqArgs,
+    collation: Option<Collation>,
+}
+
+impl From<UniqArgs> for UniqFilter {
+    fn from(args: UniqArgs) -> Self {
+        let collation = literal_collation(&args.locale);
+        Self { args, collation }
+    }
+}
 
 impl Filter for UniqFilter {
-    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> Result<Value> {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> Result<Value> {
         // TODO(#267) optional property parameter
+        let args = self.args.evaluate(runtime)?;
+        let collation = collation(self.collation.as_ref(), args.locale, runtime)?;
 
         let array = input
             .as_array()
             .ok_or_else(|| invalid_input("Array expected"))?;
         let mut deduped: Vec<Value> = Vec::with_capacity(array.size() as usize);
         for x in array.values() {
-            if !deduped
-                .iter()
-                .any(|v| ValueViewCmp::new(v.as_view()) == ValueViewCmp::new(x))
-            {
+            if !deduped.iter().any(|v| {
+                match collation
+                    .as_ref()
+                    .and_then(|c| c.compare_values(v.as_view(), x))
+                {
+                    Some(ordering) => ordering.is_eq(),
+                    None => ValueViewCmp::new(v.as_view()) == ValueViewCmp::new(x),
+                }
+            }) {
                 deduped.push(x.to_value())
             }
         }
@@ -33,6 +33,10 @@ impl Filter for DateFilter {
         let args = self.args.evaluate(runtime)?;
 
         let date = input.as_scalar().and_then(|s| s.to_date_time());
+        let date = match runtime.timezone() {
+            Some(tz) => date.map(|date| date.with_timezone(tz)),
+            None => date,
+        };
         match date {
             Some(date) if !args.format.is_empty() => {
                 let s = date.format(args.format.as_str()).map_err(|_err| {
@@ -74,8 +78,41 @@ mod tests {
     #[test]
     fn unit_date_bad_input_type() {
         assert_eq!(
-            liquid_core::call_filter!(Date, 0f64, "%Y-%m-%d").unwrap(),
-            Value::scalar(0f64)
+            liquid_core::call_filter!(Date, true, "%Y-%m-%d").unwrap(),
+            Value::scalar(true)
+        );
+    }
+
+    #[test]
+    fn unit_date_rfc3339() {
+        assert_eq!(
+            liquid_core::call_filter!(Date, "2016-06-13T02:30:00.5Z", "%Y-%m-%d %H:%M").unwrap(),
+            liquid_core::value!("2016-06-13 02:30")
+        );
+    }
+
+    #[test]
+    fn unit_date_rfc2822() {
+        assert_eq!(
+            liquid_core::call_filter!(Date, "Mon, 13 Jun 2016 02:30:00 +0300", "%Y-%m-%d %z")
+                .unwrap(),
+            liquid_core::value!("2016-06-13 +0300")
+        );
+    }
+
+    #[test]
+    fn unit_date_epoch() {
+        assert_eq!(
+            liquid_core::call_filter!(Date, 1465785000i64, "%Y-%m-%d %H:%M").unwrap(),
+            liquid_core::value!("2016-06-13 02:30")
+        );
+        assert_eq!(
+            liquid_core::call_filter!(Date, 1465785000.5f64, "%Y-%m-%d %H:%M").unwrap(),
+            liquid_core::value!("2016-06-13 02:30")
+        );
+        assert_eq!(
+            liquid_core::call_filter!(Date, "1465785000", "%Y-%m-%d %H:%M").unwrap(),
+            liquid_core::value!("2016-06-13 02:30")
         );
     }
 
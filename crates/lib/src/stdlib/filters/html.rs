@@ -1,65 +1,23 @@
+use liquid_core::model::{escape_html, escape_html_once, ScalarCow};
 use liquid_core::Result;
 use liquid_core::Runtime;
 use liquid_core::{Display_filter, Filter, FilterReflection, ParseFilter};
 use liquid_core::{Value, ValueView};
 use regex::Regex;
 
-/// Returns the number of already escaped characters.
-fn nr_escaped(text: &str) -> usize {
-    for prefix in &["lt;", "gt;", "#39;", "quot;", "amp;"] {
-        if text.starts_with(prefix) {
-            return prefix.len();
-        }
-    }
-    0
-}
+use crate::invalid_input;
 
-// The code is adapted from
-// https://github.com/rust-lang/rust/blob/master/src/librustdoc/html/escape.rs
-// Retrieved 2016-11-19.
 fn escape(input: &dyn ValueView, once_p: bool) -> Result<Value> {
     if input.is_nil() {
         return Ok(Value::Nil);
     }
     let s = input.to_kstr();
-    let mut result = String::new();
-    let mut last = 0;
-    let mut skip = 0;
-    for (i, c) in s.char_indices() {
-        if skip > 0 {
-            skip -= 1;
-            continue;
-        }
-        match c {
-            '<' | '>' | '\'' | '"' | '&' => {
-                result.push_str(&s[last..i]);
-                last = i + 1;
-                let escaped = match c {
-                    '<' => "&lt;",
-                    '>' => "&gt;",
-                    '\'' => "&#39;",
-                    '"' => "&quot;",
-                    '&' => {
-                        if once_p {
-                            skip = nr_escaped(&s[last..]);
-                        }
-                        if skip == 0 {
-                            "&amp;"
-                        } else {
-                            "&"
-                        }
-                    }
-                    _ => unreachable!(),
-                };
-                result.push_str(escaped);
-            }
-            _ => {}
-        }
-    }
-    if last < s.len() {
-        result.push_str(&s[last..]);
-    }
-    Ok(Value::scalar(result))
+    let escaped = if once_p {
+        escape_html_once(s.as_str())
+    } else {
+        escape_html(s.as_str())
+    };
+    Ok(Value::Scalar(ScalarCow::safe(escaped.into_owned())))
 }
 
 #[derive(Clone, ParseFilter, FilterReflection)]
@@ -98,6 +56,183 @@ impl Filter for EscapeOnceFilter {
     }
 }
 
+#[derive(Clone, ParseFilter, FilterReflection)]
+#[filter(
+    name = "escape_js",
+    description = "Escapes a string for use within a JavaScript string literal.",
+    parsed(EscapeJsFilter)
+)]
+pub struct EscapeJs;
+
+#[derive(Debug, Default, Display_filter)]
+#[name = "escape_js"]
+struct EscapeJsFilter;
+
+impl Filter for EscapeJsFilter {
+    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> Result<Value> {
+        if input.is_nil() {
+            return Ok(Value::Nil);
+        }
+        let s = input.to_kstr();
+        let mut result = String::with_capacity(s.len());
+        for c in s.chars() {
+            match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | ' ' | '_' | '.' | ',' | '-' => result.push(c),
+                // Line terminators in JavaScript but not in JSON
+                '\u{2028}' | '\u{2029}' => result.push_str(&format!("\\u{:04x}", c as u32)),
+                c if c.is_ascii() => result.push_str(&format!("\\x{:02x}", c as u32)),
+                c => result.push(c),
+            }
+        }
+        // Nothing special to HTML is left, so it can be output as-is.
+        Ok(Value::Scalar(ScalarCow::safe(result)))
+    }
+}
+
+#[derive(Clone, ParseFilter, FilterReflection)]
+#[filter(
+    name = "escape_json",
+    description = "Converts a value to JSON that can be embedded in an HTML `<script>` element.",
+    parsed(EscapeJsonFilter)
+)]
+pub struct EscapeJson;
+
+#[derive(Debug, Default, Display_filter)]
+#[name = "escape_json"]
+struct EscapeJsonFilter;
+
+impl Filter for EscapeJsonFilter {
+    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> Result<Value> {
+        let json =
+            serde_json::to_string(&input.to_value()).map_err(|e| invalid_input(e.to_string()))?;
+        // `<`, `>` and `&` can only appear within JSON strings, so `\u` escapes keep the meaning
+        // while preventing `</script>` and `<!--` from ending the element early.
+        let mut result = String::with_capacity(json.len());
+        for c in json.chars() {
+            match c {
+                '<' | '>' | '&' | '\'' | '\u{2028}' | '\u{2029}' => {
+                    result.push_str(&format!("\\u{:04x}", c as u32))
+                }
+                c => result.push(c),
+            }
+        }
+        Ok(Value::Scalar(ScalarCow::safe(result)))
+    }
+}
+
+#[derive(Clone, ParseFilter, FilterReflection)]
+#[filter(
+    name = "escape_css",
+    description = "Escapes a string for use within a CSS identifier or string.",
+    parsed(EscapeCssFilter)
+)]
+pub struct EscapeCss;
+
+#[derive(Debug, Default, Display_filter)]
+#[name = "escape_css"]
+struct EscapeCssFilter;
+
+impl Filter for EscapeCssFilter {
+    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> Result<Value> {
+        if input.is_nil() {
+            return Ok(Value::Nil);
+        }
+        let s = input.to_kstr();
+        let mut result = String::with_capacity(s.len());
+        for (i, c) in s.chars().enumerate() {
+            match c {
+                // Identifiers can't start with a digit
+                '0'..='9' if i == 0 => result.push_str(&format!("\\{:x} ", c as u32)),
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' => result.push(c),
+                '\0' => result.push_str("\\fffd "),
+                // The trailing space ends the escape, so a following hex digit isn't swallowed
+                c if c.is_ascii() => result.push_str(&format!("\\{:x} ", c as u32)),
+                c => result.push(c),
+            }
+        }
+        Ok(Value::Scalar(ScalarCow::safe(result)))
+    }
+}
+
+/// URL schemes that can't run code.
+const SAFE_SCHEMES: &[&str] = &["http", "https", "mailto", "tel", "ftp"];
+
+/// Characters allowed in a URL without percent-encoding.
+fn is_url_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "-._~:/?#[]@!$&()*+,;=%".contains(c)
+}
+
+#[derive(Clone, ParseFilter, FilterReflection)]
+#[filter(
+    name = "escape_url",
+    description = "Escapes a URL for use as an HTML attribute, replacing unsafe schemes like `javascript:` with `#`.",
+    parsed(EscapeUrlFilter)
+)]
+pub struct EscapeUrl;
+
+#[derive(Debug, Default, Display_filter)]
+#[name = "escape_url"]
+struct EscapeUrlFilter;
+
+impl Filter for EscapeUrlFilter {
+    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> Result<Value> {
+        if input.is_nil() {
+            return Ok(Value::Nil);
+        }
+        let s = input.to_kstr();
+        let s = s.trim();
+
+        // Browsers ignore whitespace and control characters within the scheme.
+        let scheme_end = s.find([':', '/', '?', '#']);
+        if let Some(end) = scheme_end.filter(|end| s[*end..].starts_with(':')) {
+            let scheme: String = s[..end]
+                .chars()
+                .filter(|c| !c.is_whitespace() && !c.is_control())
+                .collect::<String>()
+                .to_ascii_lowercase();
+            if !SAFE_SCHEMES.contains(&scheme.as_str()) {
+                return Ok(Value::Scalar(ScalarCow::safe("#")));
+            }
+        }
+
+        let mut result = String::with_capacity(s.len());
+        for c in s.chars() {
+            match c {
+                '&' => result.push_str("&amp;"),
+                c if is_url_char(c) => result.push(c),
+                c => {
+                    let mut buf = [0; 4];
+                    for b in c.encode_utf8(&mut buf).bytes() {
+                        result.push_str(&format!("%{:02X}", b));
+                    }
+                }
+            }
+        }
+        Ok(Value::Scalar(ScalarCow::safe(result)))
+    }
+}
+
+#[derive(Clone, ParseFilter, FilterReflection)]
+#[filter(
+    name = "raw",
+    description = "Marks a string as safe HTML so it is not auto-escaped.",
+    parsed(RawFilter)
+)]
+pub struct Raw;
+
+#[derive(Debug, Default, Display_filter)]
+#[name = "raw"]
+struct RawFilter;
+
+impl Filter for RawFilter {
+    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> Result<Value> {
+        if input.is_nil() {
+            return Ok(Value::Nil);
+        }
+        Ok(Value::Scalar(ScalarCow::safe(input.to_kstr().into_owned())))
+    }
+}
+
 #[derive(Clone, ParseFilter, FilterReflection)]
 #[filter(
     name = "strip_html",
@@ -190,6 +325,108 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unit_escape_is_safe() {
+        let escaped = liquid_core::call_filter!(Escape, "<br>").unwrap();
+        assert!(escaped.as_scalar().unwrap().is_safe());
+    }
+
+    #[test]
+    fn unit_escape_js() {
+        assert_eq!(
+            liquid_core::call_filter!(EscapeJs, "It's \"1 < 2\"\n").unwrap(),
+            liquid_core::value!(r#"It\x27s \x221 \x3c 2\x22\x0a"#)
+        );
+        assert_eq!(
+            liquid_core::call_filter!(EscapeJs, "</script>\\").unwrap(),
+            liquid_core::value!(r#"\x3c\x2fscript\x3e\x5c"#)
+        );
+        assert_eq!(
+            liquid_core::call_filter!(EscapeJs, "a\u{2028}é").unwrap(),
+            liquid_core::value!("a\\u2028é")
+        );
+    }
+
+    #[test]
+    fn unit_escape_json() {
+        assert_eq!(
+            liquid_core::call_filter!(EscapeJson, "</script><!--").unwrap(),
+            liquid_core::value!(r#""\u003c/script\u003e\u003c!--""#)
+        );
+        assert_eq!(
+            liquid_core::call_filter!(EscapeJson, liquid_core::value!({"a": [1, true, nil]}))
+                .unwrap(),
+            liquid_core::value!(r#"{"a":[1,true,null]}"#)
+        );
+        assert_eq!(
+            liquid_core::call_filter!(EscapeJson, "Tom & Jerry's").unwrap(),
+            liquid_core::value!(r#""Tom \u0026 Jerry\u0027s""#)
+        );
+    }
+
+    #[test]
+    fn unit_escape_css() {
+        assert_eq!(
+            liquid_core::call_filter!(EscapeCss, "main-nav_2").unwrap(),
+            liquid_core::value!("main-nav_2")
+        );
+        assert_eq!(
+            liquid_core::call_filter!(EscapeCss, "1a b").unwrap(),
+            liquid_core::value!("\\31 a\\20 b")
+        );
+        assert_eq!(
+            liquid_core::call_filter!(EscapeCss, "</style>").unwrap(),
+            liquid_core::value!("\\3c \\2f style\\3e ")
+        );
+    }
+
+    #[test]
+    fn unit_escape_url() {
+        assert_eq!(
+            liquid_core::call_filter!(EscapeUrl, "https://example.com/a b?x=1&y='2'").unwrap(),
+            liquid_core::value!("https://example.com/a%20b?x=1&amp;y=%272%27")
+        );
+        assert_eq!(
+            liquid_core::call_filter!(EscapeUrl, "/search?q=\"><script>").unwrap(),
+            liquid_core::value!("/search?q=%22%3E%3Cscript%3E")
+        );
+        assert_eq!(
+            liquid_core::call_filter!(EscapeUrl, "ünïcode").unwrap(),
+            liquid_core::value!("%C3%BCn%C3%AFcode")
+        );
+    }
+
+    #[test]
+    fn unit_escape_url_unsafe_scheme() {
+        assert_eq!(
+            liquid_core::call_filter!(EscapeUrl, "javascript:alert(1)").unwrap(),
+            liquid_core::value!("#")
+        );
+        assert_eq!(
+            liquid_core::call_filter!(EscapeUrl, " JaVa\tScript:alert(1)").unwrap(),
+            liquid_core::value!("#")
+        );
+        assert_eq!(
+            liquid_core::call_filter!(EscapeUrl, "data:text/html,<b>").unwrap(),
+            liquid_core::value!("#")
+        );
+        assert_eq!(
+            liquid_core::call_filter!(EscapeUrl, "mailto:me@example.com").unwrap(),
+            liquid_core::value!("mailto:me@example.com")
+        );
+        assert_eq!(
+            liquid_core::call_filter!(EscapeUrl, "page?next=javascript:x").unwrap(),
+            liquid_core::value!("page?next=javascript:x")
+        );
+    }
+
+    #[test]
+    fn unit_raw() {
+        let raw = liquid_core::call_filter!(Raw, "<br>").unwrap();
+        assert_eq!(raw, liquid_core::value!("<br>"));
+        assert!(raw.as_scalar().unwrap().is_safe());
+    }
+
     #[test]
     fn unit_strip_html() {
         assert_eq!(
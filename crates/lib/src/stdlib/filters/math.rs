@@ -1,5 +1,7 @@
 use std::convert::TryInto;
 
+#[cfg(feature = "decimal")]
+use liquid_core::model::{Decimal, ScalarCow};
 use liquid_core::Expression;
 use liquid_core::Result;
 use liquid_core::Runtime;
@@ -10,6 +12,22 @@ use liquid_core::{Value, ValueView};
 
 use crate::{invalid_argument, invalid_input};
 
+/// Both operands as exact decimals, when either is one, so money stays exact.
+#[cfg(feature = "decimal")]
+fn decimal_operands(input: &ScalarCow<'_>, operand: &ScalarCow<'_>) -> Option<(Decimal, Decimal)> {
+    if !input.is_decimal() && !operand.is_decimal() {
+        return None;
+    }
+    input.to_decimal().zip(operand.to_decimal())
+}
+
+#[cfg(feature = "decimal")]
+fn decimal_result(result: Option<Decimal>) -> Result<Value> {
+    result
+        .map(Value::scalar)
+        .ok_or_else(|| invalid_input("Decimal overflow"))
+}
+
 #[derive(Clone, ParseFilter, FilterReflection)]
 #[filter(
     name = "abs",
@@ -27,6 +45,10 @@ impl Filter for AbsFilter {
         let input = input
             .as_scalar()
             .ok_or_else(|| invalid_input("Number expected"))?;
+        #[cfg(feature = "decimal")]
+        if input.is_decimal() {
+            return decimal_result(input.to_decimal().map(|i| i.abs()));
+        }
         input
             .to_integer()
             .map(|i| Value::scalar(i.abs()))
@@ -70,6 +92,11 @@ impl Filter for AtLeastFilter {
             .as_scalar()
             .ok_or_else(|| invalid_argument("operand", "Number expected"))?;
 
+        #[cfg(feature = "decimal")]
+        if let Some((i, o)) = decimal_operands(&input, &min) {
+            return decimal_result(Some(i.max(o)));
+        }
+
         let result = input
             .to_integer()
             .and_then(|i| min.to_integer().map(|min| Value::scalar(i.max(min))))
@@ -119,6 +146,11 @@ impl Filter for AtMostFilter {
             .as_scalar()
             .ok_or_else(|| invalid_argument("operand", "Number expected"))?;
 
+        #[cfg(feature = "decimal")]
+        if let Some((i, o)) = decimal_operands(&input, &max) {
+            return decimal_result(Some(i.min(o)));
+        }
+
         let result = input
             .to_integer()
             .and_then(|i| max.to_integer().map(|max| Value::scalar(i.min(max))))
@@ -168,6 +200,11 @@ impl Filter for PlusFilter {
             .as_scalar()
             .ok_or_else(|| invalid_argument("operand", "Number expected"))?;
 
+        #[cfg(feature = "decimal")]
+        if let Some((i, o)) = decimal_operands(&input, &operand) {
+            return decimal_result(i.checked_add(o));
+        }
+
         let result = input
             .to_integer()
             .and_then(|i| operand.to_integer().map(|o| Value::scalar(i + o)))
@@ -217,6 +254,11 @@ impl Filter for MinusFilter {
             .as_scalar()
             .ok_or_else(|| invalid_argument("operand", "Number expected"))?;
 
+        #[cfg(feature = "decimal")]
+        if let Some((i, o)) = decimal_operands(&input, &operand) {
+            return decimal_result(i.checked_sub(o));
+        }
+
         let result = input
             .to_integer()
             .and_then(|i| operand.to_integer().map(|o| Value::scalar(i - o)))
@@ -266,6 +308,11 @@ impl Filter for TimesFilter {
             .as_scalar()
             .ok_or_else(|| invalid_argument("operand", "Number expected"))?;
 
+        #[cfg(feature = "decimal")]
+        if let Some((i, o)) = decimal_operands(&input, &operand) {
+            return decimal_result(i.checked_mul(o));
+        }
+
         let result = input
             .to_integer()
             .and_then(|i| operand.to_integer().map(|o| Value::scalar(i * o)))
@@ -325,6 +372,11 @@ impl Filter for DividedByFilter {
             }
         }
 
+        #[cfg(feature = "decimal")]
+        if let Some((i, o)) = decimal_operands(&input, &operand) {
+            return decimal_result(i.checked_div(o));
+        }
+
         let result = input
             .to_integer()
             .and_then(|i| operand.to_integer().map(|o| Value::scalar(i / o)))
@@ -384,6 +436,11 @@ impl Filter for ModuloFilter {
             }
         }
 
+        #[cfg(feature = "decimal")]
+        if let Some((i, o)) = decimal_operands(&input, &operand) {
+            return decimal_result(i.checked_rem(o));
+        }
+
         let result = input
             .to_integer()
             .and_then(|i| operand.to_integer().map(|o| Value::scalar(i % o)))
@@ -407,6 +464,29 @@ struct RoundArgs {
     decimal_places: Option<Expression>,
 }
 
+/// Round to `n` decimal places, or to the nearest `10^-n` when `n` is negative.
+#[cfg(feature = "decimal")]
+fn round_decimal(input: Decimal, n: i64) -> Option<Decimal> {
+    use rust_decimal::RoundingStrategy::MidpointAwayFromZero;
+
+    if 0 <= n {
+        let dp = n.try_into().unwrap_or(u32::MAX);
+        return Some(input.round_dp_with_strategy(dp, MidpointAwayFromZero));
+    }
+
+    let mut divisor = Decimal::ONE;
+    for _ in 0..n.unsigned_abs() {
+        match divisor.checked_mul(Decimal::TEN) {
+            Some(next) => divisor = next,
+            // Larger than any decimal, so everything rounds to zero.
+            None => return Some(Decimal::ZERO),
+        }
+    }
+    (input / divisor)
+        .round_dp_with_strategy(0, MidpointAwayFromZero)
+        .checked_mul(divisor)
+}
+
 #[derive(Clone, ParseFilter, FilterReflection)]
 #[filter(
     name = "round",
@@ -431,7 +511,15 @@ impl Filter for RoundFilter {
 
         let input = input
             .as_scalar()
-            .and_then(|s| s.to_float())
+            .ok_or_else(|| invalid_input("Number expected"))?;
+
+        #[cfg(feature = "decimal")]
+        if input.is_decimal() {
+            return decimal_result(input.to_decimal().and_then(|i| round_decimal(i, n)));
+        }
+
+        let input = input
+            .to_float()
             .ok_or_else(|| invalid_input("Number expected"))?;
 
         match n.cmp(&0) {
@@ -464,7 +552,13 @@ impl Filter for CeilFilter {
     fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> Result<Value> {
         let n = input
             .as_scalar()
-            .and_then(|s| s.to_float())
+            .ok_or_else(|| invalid_input("Number expected"))?;
+        #[cfg(feature = "decimal")]
+        if n.is_decimal() {
+            return decimal_result(n.to_decimal().map(|n| n.ceil()));
+        }
+        let n = n
+            .to_float()
             .ok_or_else(|| invalid_input("Number expected"))?;
         Ok(Value::scalar(n.ceil() as i64))
     }
@@ -486,7 +580,13 @@ impl Filter for FloorFilter {
     fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> Result<Value> {
         let n = input
             .as_scalar()
-            .and_then(|s| s.to_float())
+            .ok_or_else(|| invalid_input("Number expected"))?;
+        #[cfg(feature = "decimal")]
+        if n.is_decimal() {
+            return decimal_result(n.to_decimal().map(|n| n.floor()));
+        }
+        let n = n
+            .to_float()
             .ok_or_else(|| invalid_input("Number expected"))?;
         Ok(Value::scalar(n.floor() as i64))
     }
@@ -708,4 +808,92 @@ mod tests {
             Value::scalar(1.235f64)
         );
     }
+
+    #[cfg(feature = "decimal")]
+    fn decimal(s: &str) -> Value {
+        Value::scalar(s.parse::<Decimal>().unwrap())
+    }
+
+    /// Like `call_filter!` but without round-tripping the input through serde, which would turn
+    /// decimals into strings.
+    #[cfg(feature = "decimal")]
+    fn call_decimal(filter: impl ParseFilter, input: Value, args: Vec<Value>) -> Result<Value> {
+        let positional = Box::new(args.into_iter().map(Expression::Literal));
+        let keyword = Box::new(Vec::new().into_iter());
+        let args = liquid_core::parser::FilterArguments {
+            positional,
+            keyword,
+        };
+        let runtime = liquid_core::runtime::RuntimeBuilder::new().build();
+        filter
+            .parse(args)
+            .and_then(|filter| filter.evaluate(&input, &runtime))
+    }
+
+    #[cfg(feature = "decimal")]
+    fn render_decimal(value: Value) -> String {
+        assert!(value.as_scalar().unwrap().is_decimal(), "{:?}", value);
+        value.render().to_string()
+    }
+
+    #[test]
+    #[cfg(feature = "decimal")]
+    fn unit_decimal_arithmetic() {
+        let price = decimal("0.10");
+        assert_eq!(
+            render_decimal(call_decimal(Plus, price, vec![Value::scalar(0.2f64)]).unwrap()),
+            "0.30"
+        );
+        let price = decimal("19.99");
+        assert_eq!(
+            render_decimal(call_decimal(Times, price, vec![Value::scalar(1.1f64)]).unwrap()),
+            "21.989"
+        );
+        let price = decimal("10");
+        let quotient = call_decimal(DividedBy, price, vec![Value::scalar(4i64)]).unwrap();
+        assert!(quotient.as_scalar().unwrap().is_decimal());
+        assert_eq!(quotient, Value::scalar(2.5f64));
+        let price = decimal("10");
+        call_decimal(DividedBy, price, vec![Value::scalar(0i64)]).unwrap_err();
+        let price = decimal("10.50");
+        assert_eq!(
+            render_decimal(call_decimal(Minus, price, vec![Value::scalar("0.25")]).unwrap()),
+            "10.25"
+        );
+        let price = decimal("-3.50");
+        assert_eq!(
+            render_decimal(call_decimal(AtLeast, price, vec![Value::scalar(0i64)]).unwrap()),
+            "0"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "decimal")]
+    fn unit_decimal_round() {
+        let price = decimal("2.675");
+        assert_eq!(
+            render_decimal(call_decimal(Round, price, vec![Value::scalar(2i64)]).unwrap()),
+            "2.68"
+        );
+        let price = decimal("2.5");
+        assert_eq!(
+            render_decimal(call_decimal(Round, price, vec![]).unwrap()),
+            "3"
+        );
+        let price = decimal("1254.5");
+        assert_eq!(
+            render_decimal(call_decimal(Round, price, vec![Value::scalar(-1i64)]).unwrap()),
+            "1250"
+        );
+        let price = decimal("1254.5");
+        assert_eq!(
+            render_decimal(call_decimal(Round, price, vec![Value::scalar(-40i64)]).unwrap()),
+            "0"
+        );
+        let price = decimal("2.1");
+        assert_eq!(
+            render_decimal(call_decimal(Ceil, price, vec![]).unwrap()),
+            "3"
+        );
+    }
 }
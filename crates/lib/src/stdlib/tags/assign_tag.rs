@@ -2,6 +2,7 @@ use std::io::Write;
 
 use liquid_core::error::ResultLiquidExt;
 use liquid_core::parser::FilterChain;
+use liquid_core::runtime::Analyzer;
 use liquid_core::Language;
 use liquid_core::Renderable;
 use liquid_core::Result;
@@ -83,6 +84,11 @@ impl Renderable for Assign {
         runtime.set_global(self.dst.clone(), value);
         Ok(())
     }
+
+    fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        self.src.analyze(analyzer);
+        analyzer.set_global(self.dst.clone());
+    }
 }
 
 #[cfg(test)]
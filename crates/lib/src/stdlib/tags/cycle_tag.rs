@@ -1,9 +1,12 @@
 use std::collections::HashMap;
 use std::io::Write;
 
-use liquid_core::error::{ResultLiquidExt, ResultLiquidReplaceExt};
+use liquid_core::error::ResultLiquidExt;
+use liquid_core::parser::write_value;
 use liquid_core::parser::TagToken;
 use liquid_core::parser::TryMatchToken;
+use liquid_core::runtime::Analyzer;
+use liquid_core::runtime::{is_tainted, report_tainted, TaintMode};
 use liquid_core::Expression;
 use liquid_core::Language;
 use liquid_core::Renderable;
@@ -46,7 +49,7 @@ impl ParseTag for CycleTag {
 }
 
 /// Internal implementation of cycle, to allow easier testing.
-fn parse_cycle(mut arguments: TagTokenIter<'_>, _options: &Language) -> Result<Cycle> {
+fn parse_cycle(mut arguments: TagTokenIter<'_>, options: &Language) -> Result<Cycle> {
     let mut name = String::new();
     let mut values = Vec::new();
 
@@ -99,13 +102,18 @@ fn parse_cycle(mut arguments: TagTokenIter<'_>, _options: &Language) -> Result<C
     // no more arguments should be supplied, trying to supply them is an error
     arguments.expect_nothing()?;
 
-    Ok(Cycle { name, values })
+    Ok(Cycle {
+        name,
+        values,
+        escape: options.auto_escape,
+    })
 }
 
 #[derive(Clone, Debug)]
 struct Cycle {
     name: String,
     values: Vec<Expression>,
+    escape: bool,
 }
 
 impl Cycle {
@@ -125,8 +133,16 @@ impl Renderable for Cycle {
             .cycle(&self.name, &self.values)
             .trace_with(|| self.trace().into())?;
         let value = expr.evaluate(runtime).trace_with(|| self.trace().into())?;
-        write!(writer, "{}", value.render()).replace("Failed to render")?;
-        Ok(())
+        if !self.escape && runtime.taint_mode() != TaintMode::Lax && is_tainted(value.as_view()) {
+            report_tainted(runtime, expr)?;
+        }
+        write_value(writer, value.as_view(), self.escape)
+    }
+
+    fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        for value in &self.values {
+            value.analyze(analyzer);
+        }
     }
 }
 
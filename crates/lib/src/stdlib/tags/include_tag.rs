@@ -2,9 +2,12 @@ use std::io::Write;
 
 use liquid_core::error::ResultLiquidExt;
 use liquid_core::model::KString;
+use liquid_core::model::KStringRef;
+use liquid_core::runtime::Analyzer;
 use liquid_core::Expression;
 use liquid_core::Language;
 use liquid_core::Renderable;
+use liquid_core::ValueCow;
 use liquid_core::ValueView;
 use liquid_core::{runtime::StackFrame, Runtime};
 use liquid_core::{Error, Result};
@@ -39,8 +42,44 @@ impl ParseTag for IncludeTag {
 
         let partial = partial.expect_value().into_result()?;
 
+        let mut token = arguments.next();
+        let mut variable = None;
+        if let Some(keyword) = token.as_ref().map(|t| t.as_str()) {
+            if keyword == "with" || keyword == "for" {
+                variable = Some(
+                    arguments
+                        .expect_next("expected value")?
+                        .expect_value()
+                        .into_result()?,
+                );
+                token = arguments.next();
+            }
+        }
+
+        let mut alias = None;
+        if variable.is_some() && token.as_ref().map(|t| t.as_str()) == Some("as") {
+            alias = Some(
+                arguments
+                    .expect_next("Identifier expected.")?
+                    .expect_identifier()
+                    .into_result()?
+                    .to_string()
+                    .into(),
+            );
+            token = arguments.next();
+        }
+
+        if variable.is_some() {
+            // the variable may be separated from the assignments by a comma
+            if let Some(comma) = token.as_ref() {
+                if comma.as_str() == "," {
+                    token = arguments.next();
+                }
+            }
+        }
+
         let mut vars: Vec<(KString, Expression)> = Vec::new();
-        while let Ok(next) = arguments.expect_next("") {
+        while let Some(next) = token {
             let id = next.expect_identifier().into_result()?.to_string();
 
             arguments
@@ -56,18 +95,28 @@ impl ParseTag for IncludeTag {
                     .into_result()?,
             ));
 
-            if let Ok(comma) = arguments.expect_next("") {
+            token = arguments.next();
+            if let Some(comma) = token.as_ref() {
                 // stop looking for variables if there is no comma
                 // currently allows for one trailing comma
-                if comma.expect_str(",").into_result().is_err() {
+                if comma.as_str() != "," {
                     break;
                 }
+                token = arguments.next();
             }
         }
 
+        if let Some(token) = token {
+            return token.raise_error().into_err();
+        }
         arguments.expect_nothing()?;
 
-        Ok(Box::new(Include { partial, vars }))
+        Ok(Box::new(Include {
+            partial,
+            variable,
+            alias,
+            vars,
+        }))
     }
 
     fn reflection(&self) -> &dyn TagReflection {
@@ -78,9 +127,44 @@ impl ParseTag for IncludeTag {
 #[derive(Debug)]
 struct Include {
     partial: Expression,
+    /// From `with` or `for`
+    variable: Option<Expression>,
+    /// What to call `variable`, instead of the partial's name
+    alias: Option<KString>,
     vars: Vec<(KString, Expression)>,
 }
 
+impl Include {
+    fn trace(&self) -> String {
+        format!("{{% include {} %}}", self.partial)
+    }
+
+    fn render_partial(
+        &self,
+        name: &str,
+        pass_through: &std::collections::HashMap<KStringRef<'_>, ValueCow<'_>>,
+        writer: &mut dyn Write,
+        runtime: &dyn Runtime,
+    ) -> Result<()> {
+        let scope = StackFrame::new(runtime, pass_through);
+        let partial = scope
+            .partials()
+            .get(name)
+            .trace_with(|| self.trace().into())?;
+
+        partial
+            .render_to(writer, &scope)
+            .trace_with(|| self.trace().into())
+            .context_key_with(|| self.partial.to_string().into())
+            .value_with(|| name.to_string().into())
+    }
+}
+
+/// The implicit name for the `with` or `for` variable, like Ruby's `template_name.split('/').last`.
+fn variable_name(partial: &str) -> &str {
+    partial.rsplit('/').next().unwrap_or(partial)
+}
+
 impl Renderable for Include {
     fn render_to(&self, writer: &mut dyn Write, runtime: &dyn Runtime) -> Result<()> {
         let value = self.partial.evaluate(runtime)?;
@@ -91,36 +175,67 @@ impl Renderable for Include {
         }
         let name = value.to_kstr().into_owned();
 
-        {
-            // if there our additional variables creates a include object to access all the variables
-            // from e.g. { include 'image.html' path="foo.png" }
-            // then in image.html you could have <img src="{{include.path}}" />
-            let mut pass_through = std::collections::HashMap::new();
-            if !self.vars.is_empty() {
-                for (id, val) in &self.vars {
-                    let value = val
-                        .try_evaluate(runtime)
-                        .ok_or_else(|| Error::with_msg("failed to evaluate value"))?;
-
-                    pass_through.insert(id.as_ref(), value);
-                }
-            }
+        // if there our additional variables creates a include object to access all the variables
+        // from e.g. { include 'image.html' path="foo.png" }
+        // then in image.html you could have <img src="{{include.path}}" />
+        let mut pass_through = std::collections::HashMap::new();
+        for (id, val) in &self.vars {
+            let value = val
+                .try_evaluate(runtime)
+                .ok_or_else(|| Error::with_msg("failed to evaluate value"))?;
+
+            pass_through.insert(id.as_ref(), value);
+        }
 
-            let scope = StackFrame::new(runtime, &pass_through);
-            let partial = scope
-                .partials()
-                .get(&name)
-                .trace_with(|| format!("{{% include {} %}}", self.partial).into())?;
-
-            partial
-                .render_to(writer, &scope)
-                .trace_with(|| format!("{{% include {} %}}", self.partial).into())
-                .context_key_with(|| self.partial.to_string().into())
-                .value_with(|| name.to_string().into())?;
+        let Some(variable) = &self.variable else {
+            return self.render_partial(&name, &pass_through, writer, runtime);
+        };
+        let variable = variable
+            .evaluate(runtime)
+            .trace_with(|| self.trace().into())?;
+        let variable_name = self
+            .alias
+            .as_ref()
+            .map(|a| a.as_ref())
+            .unwrap_or_else(|| KStringRef::from_ref(variable_name(&name)));
+
+        // Like Ruby, both `with` and `for` include the partial once per item of an array
+        if let Some(array) = variable.as_array() {
+            for item in array.values() {
+                pass_through.insert(variable_name, ValueCow::Borrowed(item));
+                self.render_partial(&name, &pass_through, writer, runtime)?;
+            }
+        } else {
+            pass_through.insert(variable_name, ValueCow::Borrowed(variable.as_view()));
+            self.render_partial(&name, &pass_through, writer, runtime)?;
         }
 
         Ok(())
     }
+
+    fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        for (_, val) in &self.vars {
+            val.analyze(analyzer);
+        }
+        if let Some(variable) = &self.variable {
+            variable.analyze(analyzer);
+        }
+        match self.partial {
+            Expression::Literal(ref name) => {
+                let name = name.to_kstr();
+                let mut vars: Vec<_> = self.vars.iter().map(|(id, _)| id.clone()).collect();
+                if self.variable.is_some() {
+                    let variable_name = self
+                        .alias
+                        .clone()
+                        .unwrap_or_else(|| KString::from_ref(variable_name(name.as_str())));
+                    vars.push(variable_name);
+                }
+                analyzer.include_partial(name.as_str(), &vars);
+            }
+            Expression::Variable(_) => self.partial.analyze(analyzer),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -274,6 +389,28 @@ mod test {
         assert_eq!(output, "hello dogs");
     }
 
+    #[test]
+    fn include_for_alias() {
+        let text = "{% include 'example_multi_var.txt' for items as example_var, example: 'x' %}";
+        let options = options();
+        let template = parser::parse(text, &options)
+            .map(runtime::Template::new)
+            .unwrap();
+
+        let partials = partials::OnDemandCompiler::<TestSource>::empty()
+            .compile(::std::sync::Arc::new(options))
+            .unwrap();
+        let runtime = RuntimeBuilder::new()
+            .set_partials(partials.as_ref())
+            .build();
+        runtime.set_global(
+            "items".into(),
+            Value::Array(vec![Value::scalar(1), Value::scalar(2)]),
+        );
+        let output = template.render(&runtime).unwrap();
+        assert_eq!(output, "1 x2 x");
+    }
+
     #[test]
     fn no_file() {
         let text = "{% include 'file_does_not_exist.liquid' %}";
@@ -2,6 +2,7 @@ use std::io::Write;
 
 use liquid_core::error::ResultLiquidReplaceExt;
 use liquid_core::model::{Value, ValueView};
+use liquid_core::runtime::Analyzer;
 use liquid_core::Language;
 use liquid_core::Renderable;
 use liquid_core::Result;
@@ -68,6 +69,10 @@ impl Renderable for Increment {
         runtime.set_index(self.id.clone(), Value::scalar(val));
         Ok(())
     }
+
+    fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        analyzer.set_global(self.id.clone());
+    }
 }
 
 #[derive(Copy, Clone, Debug, Default)]
@@ -130,6 +135,10 @@ impl Renderable for Decrement {
         runtime.set_index(self.id.clone(), Value::scalar(val));
         Ok(())
     }
+
+    fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        analyzer.set_global(self.id.clone());
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,6 @@
 use std::io::Write;
 
-use liquid_core::runtime::{Interrupt, InterruptRegister};
+use liquid_core::runtime::{Analyzer, Interrupt, InterruptRegister};
 use liquid_core::Language;
 use liquid_core::Renderable;
 use liquid_core::Result;
@@ -53,6 +53,8 @@ impl Renderable for Break {
             .set(Interrupt::Break);
         Ok(())
     }
+
+    fn analyze(&self, _analyzer: &mut Analyzer<'_>) {}
 }
 
 #[derive(Copy, Clone, Debug, Default)]
@@ -101,6 +103,8 @@ impl Renderable for Continue {
             .set(Interrupt::Continue);
         Ok(())
     }
+
+    fn analyze(&self, _analyzer: &mut Analyzer<'_>) {}
 }
 
 #[cfg(test)]
@@ -1,11 +1,14 @@
 use std::io::Write;
+use std::sync::Arc;
 
 use liquid_core::error::ResultLiquidExt;
 use liquid_core::model::KString;
 use liquid_core::parser::TryMatchToken;
+use liquid_core::runtime::Analyzer;
 use liquid_core::runtime::GlobalFrame;
 use liquid_core::runtime::Interrupt;
 use liquid_core::runtime::InterruptRegister;
+use liquid_core::runtime::PartialStore;
 use liquid_core::runtime::SandboxedStackFrame;
 use liquid_core::Expression;
 use liquid_core::Language;
@@ -184,10 +187,7 @@ impl Renderable for Render {
 
                     let scope = GlobalFrame::new(SandboxedStackFrame::new(runtime, &root));
 
-                    let partial = scope
-                        .partials()
-                        .get(&name)
-                        .or_else(|_| scope.partials().get(&format!("{name}.liquid")))
+                    let partial = get_partial(scope.partials(), &name)
                         .trace_with(|| format!("{{% render {} %}}", self.partial).into())?;
 
                     partial
@@ -219,10 +219,7 @@ impl Renderable for Render {
 
             let scope = GlobalFrame::new(SandboxedStackFrame::new(runtime, &root));
 
-            let partial = scope
-                .partials()
-                .get(&name)
-                .or_else(|_| scope.partials().get(&format!("{name}.liquid")))
+            let partial = get_partial(scope.partials(), &name)
                 .trace_with(|| format!("{{% render {} %}}", self.partial).into())?;
 
             partial
@@ -234,6 +231,44 @@ impl Renderable for Render {
 
         Ok(())
     }
+
+    fn analyze(&self, analyzer: &mut Analyzer<'_>) {
+        let mut vars: Vec<_> = self.vars.iter().map(|(id, _)| id.clone()).collect();
+        for (_, val) in &self.vars {
+            val.analyze(analyzer);
+        }
+        if let Some((range, var_name)) = &self.for_ {
+            range.analyze(analyzer);
+            vars.push("forloop".into());
+            vars.push(var_name.clone());
+        }
+        match self.partial {
+            Expression::Literal(ref name) => {
+                let name = name.to_kstr();
+                let name = if analyzer.partials().contains(&name) {
+                    name.into_owned()
+                } else {
+                    let with_extension = format!("{name}.liquid");
+                    if analyzer.partials().contains(&with_extension) {
+                        with_extension.into()
+                    } else {
+                        name.into_owned()
+                    }
+                };
+                analyzer.render_partial(&name, &vars);
+            }
+            Expression::Variable(_) => self.partial.analyze(analyzer),
+        }
+    }
+}
+
+/// Look up `name`, falling back to `name.liquid`.
+///
+/// If neither works, the error for `name` is kept, like hitting a sandbox limit.
+fn get_partial(partials: &dyn PartialStore, name: &str) -> Result<Arc<dyn Renderable>> {
+    partials
+        .get(name)
+        .or_else(|error| partials.get(&format!("{name}.liquid")).map_err(|_| error))
 }
 
 #[cfg(test)]
@@ -0,0 +1,179 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync;
+
+use liquid_core::error::Result;
+
+use super::Template;
+
+/// How well `Parser::parse_cached` is doing.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CacheStats {
+    /// Templates served from the cache.
+    pub hits: u64,
+    /// Templates that had to be parsed.
+    pub misses: u64,
+    /// Templates currently cached.
+    pub len: usize,
+    /// The most templates that will be cached.
+    pub capacity: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum CacheKey {
+    Source(String),
+    Named(String, u64),
+}
+
+impl CacheKey {
+    fn new(name: Option<&str>, text: &str) -> Self {
+        match name {
+            Some(name) => {
+                let mut hasher = DefaultHasher::new();
+                text.hash(&mut hasher);
+                CacheKey::Named(name.to_owned(), hasher.finish())
+            }
+            None => CacheKey::Source(text.to_owned()),
+        }
+    }
+}
+
+struct CacheEntry {
+    template: sync::Arc<Template>,
+    /// The text behind a `CacheKey::Named`, so a hash collision is a miss rather than the wrong
+    /// template.
+    source: Option<String>,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<CacheKey, CacheEntry>,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+}
+
+/// Parsed templates, evicting the least recently used.
+pub(crate) struct TemplateCache {
+    capacity: usize,
+    state: sync::Mutex<CacheState>,
+}
+
+impl TemplateCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Default::default(),
+        }
+    }
+
+    pub(crate) fn get_or_parse(
+        &self,
+        name: Option<&str>,
+        text: &str,
+        parse: impl FnOnce() -> Result<Template>,
+    ) -> Result<sync::Arc<Template>> {
+        let key = CacheKey::new(name, text);
+        {
+            let mut state = self.state.lock().expect("not to be poisoned and reused");
+            state.clock += 1;
+            let clock = state.clock;
+            if let Some(entry) = state
+                .entries
+                .get_mut(&key)
+                .filter(|entry| entry.source.as_deref().unwrap_or(text) == text)
+            {
+                entry.last_used = clock;
+                let template = entry.template.clone();
+                state.hits += 1;
+                return Ok(template);
+            }
+            state.misses += 1;
+        }
+
+        // Not holding the lock while parsing; a racing parse of the same template is harmless.
+        let template = sync::Arc::new(parse()?);
+        if self.capacity == 0 {
+            return Ok(template);
+        }
+
+        // Finding the least recently used entry scans the whole cache, which is cheap next to the
+        // parse that got us here for the capacities this is meant for.
+        let mut state = self.state.lock().expect("not to be poisoned and reused");
+        if !state.entries.contains_key(&key) && self.capacity <= state.entries.len() {
+            let oldest = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                state.entries.remove(&oldest);
+            }
+        }
+        let last_used = state.clock;
+        let source = match key {
+            CacheKey::Named(..) => Some(text.to_owned()),
+            CacheKey::Source(_) => None,
+        };
+        state.entries.insert(
+            key,
+            CacheEntry {
+                template: template.clone(),
+                source,
+                last_used,
+            },
+        );
+        Ok(template)
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        let state = self.state.lock().expect("not to be poisoned and reused");
+        CacheStats {
+            hits: state.hits,
+            misses: state.misses,
+            len: state.entries.len(),
+            capacity: self.capacity,
+        }
+    }
+
+    pub(crate) fn clear(&self) {
+        let mut state = self.state.lock().expect("not to be poisoned and reused");
+        state.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(text: &str) -> Result<Template> {
+        crate::ParserBuilder::new().build()?.parse(text)
+    }
+
+    #[test]
+    fn test_named_collision_is_a_miss() {
+        let cache = TemplateCache::new(2);
+        let one = cache
+            .get_or_parse(Some("page"), "one", || parse("one"))
+            .unwrap();
+
+        // Pretend "two" hashed the same as "one".
+        {
+            let mut state = cache.state.lock().unwrap();
+            let entry = state.entries.remove(&CacheKey::new(Some("page"), "one"));
+            state
+                .entries
+                .insert(CacheKey::new(Some("page"), "two"), entry.unwrap());
+        }
+
+        let two = cache
+            .get_or_parse(Some("page"), "two", || parse("two"))
+            .unwrap();
+        assert!(!sync::Arc::ptr_eq(&one, &two));
+        assert_eq!(two.render(&crate::Object::new()).unwrap(), "two");
+        assert_eq!(cache.stats().misses, 2);
+    }
+}
@@ -0,0 +1,92 @@
+use std::borrow;
+use std::sync;
+use std::time;
+
+use liquid_core::error::Result;
+use liquid_core::partials::{PartialCompiler, PartialSource, ReloadingCompiler};
+
+use super::{Parser, ParserBuilder, Template};
+
+/// Templates looked up by name, like a site's pages and layouts.
+///
+/// Top-level templates and the partial-templates used by `include`, `render` and layouts all come
+/// from the same source, so any template can be rendered directly or from another.  Edits to the
+/// source are picked up, like with `ReloadingCompiler`.
+///
+/// # Examples
+///
+/// ```
+/// use liquid::partials::InMemorySource;
+///
+/// let mut source = InMemorySource::new();
+/// source.add("pages/index", "{% include 'header' %} Welcome");
+/// source.add("header", "<h1>{{ site.title }}</h1>");
+///
+/// let env = liquid::Environment::new(
+///     liquid::ParserBuilder::with_stdlib().template_cache(100),
+///     source,
+/// ).unwrap();
+///
+/// let globals = liquid::object!({ "site": { "title": "Liquid" } });
+/// let output = env.get_template("pages/index").unwrap().render(&globals).unwrap();
+/// assert_eq!(output, "<h1>Liquid</h1> Welcome");
+/// ```
+pub struct Environment {
+    parser: Parser,
+    source: SharedSource,
+}
+
+impl Environment {
+    /// Serve templates from `source`, parsed by `builder`.
+    ///
+    /// Any partial-templates already set on `builder` are replaced by `source`.  Set
+    /// `ParserBuilder::template_cache` to only parse templates when they change.
+    pub fn new<P, S>(builder: ParserBuilder<P>, source: S) -> Result<Self>
+    where
+        P: PartialCompiler,
+        S: PartialSource + Send + Sync + 'static,
+    {
+        let source = SharedSource(sync::Arc::new(source));
+        let parser = builder
+            .partials(ReloadingCompiler::new(source.clone()))
+            .build()?;
+        Ok(Self { parser, source })
+    }
+
+    /// The parser, for templates not in the source.
+    pub fn parser(&self) -> &Parser {
+        &self.parser
+    }
+
+    /// The names of all templates.
+    pub fn names(&self) -> Vec<&str> {
+        self.source.names()
+    }
+
+    /// Look up and parse a template, named for errors and `Runtime::name`.
+    pub fn get_template(&self, name: &str) -> Result<sync::Arc<Template>> {
+        let text = self.source.get(name)?;
+        self.parser.parse_cached_named(name, &text)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SharedSource(sync::Arc<dyn PartialSource + Send + Sync>);
+
+impl PartialSource for SharedSource {
+    fn contains(&self, name: &str) -> bool {
+        self.0.contains(name)
+    }
+
+    fn names(&self) -> Vec<&str> {
+        self.0.names()
+    }
+
+    fn try_get<'a>(&'a self, name: &str) -> Option<borrow::Cow<'a, str>> {
+        self.0.try_get(name)
+    }
+
+    fn modified(&self, name: &str) -> Option<time::SystemTime> {
+        self.0.modified(name)
+    }
+}
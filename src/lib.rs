@@ -22,6 +22,8 @@
 
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 
+mod cache;
+mod environment;
 mod parser;
 mod template;
 
@@ -37,10 +39,15 @@ pub mod model {
     pub use liquid_core::value;
 }
 
+pub use crate::cache::CacheStats;
+pub use crate::environment::Environment;
 pub use crate::parser::*;
 pub use crate::template::*;
 pub use liquid_core::model::{_ObjectView as ObjectView, _ValueView as ValueView};
 pub use liquid_core::object;
+pub use liquid_core::parser::SandboxPolicy;
+pub use liquid_core::runtime::Dependencies;
+pub use liquid_core::runtime::TaintMode;
 pub use liquid_core::to_object;
 pub use liquid_core::Error;
 pub use liquid_core::Object;
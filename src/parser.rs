@@ -4,11 +4,15 @@ use std::path;
 use std::sync;
 
 use liquid_core::error::{Result, ResultLiquidExt, ResultLiquidReplaceExt};
+use liquid_core::model::KString;
 use liquid_core::parser;
 use liquid_core::runtime;
 
 use super::Template;
+use crate::cache::{CacheStats, TemplateCache};
 use crate::reflection;
+use liquid_core::model::Collation;
+use liquid_core::model::TimeZone;
 use liquid_core::partials;
 #[cfg(feature = "stdlib")]
 use liquid_lib::stdlib;
@@ -23,6 +27,10 @@ where
     tags: parser::PluginRegistry<Box<dyn parser::ParseTag>>,
     filters: parser::PluginRegistry<Box<dyn parser::ParseFilter>>,
     partials: Option<P>,
+    auto_escape: bool,
+    cache_capacity: Option<usize>,
+    timezone: Option<TimeZone>,
+    collation: Option<Collation>,
 }
 
 impl ParserBuilder<Partials> {
@@ -74,7 +82,11 @@ where
             .filter(stdlib::DividedBy)
             .filter(stdlib::Downcase)
             .filter(stdlib::Escape)
+            .filter(stdlib::EscapeCss)
+            .filter(stdlib::EscapeJs)
+            .filter(stdlib::EscapeJson)
             .filter(stdlib::EscapeOnce)
+            .filter(stdlib::EscapeUrl)
             .filter(stdlib::First)
             .filter(stdlib::Floor)
             .filter(stdlib::Join)
@@ -86,6 +98,7 @@ where
             .filter(stdlib::NewlineToBr)
             .filter(stdlib::Plus)
             .filter(stdlib::Prepend)
+            .filter(stdlib::Raw)
             .filter(stdlib::Remove)
             .filter(stdlib::RemoveFirst)
             .filter(stdlib::Replace)
@@ -141,15 +154,56 @@ where
             tags,
             filters,
             partials: _partials,
+            auto_escape,
+            cache_capacity,
+            timezone,
+            collation,
         } = self;
         ParserBuilder {
             blocks,
             tags,
             filters,
             partials: Some(partials),
+            auto_escape,
+            cache_capacity,
+            timezone,
+            collation,
         }
     }
 
+    /// HTML-escape the output of every `{{ }}`.
+    ///
+    /// Safe strings, like those from the `raw` and `escape` filters, are output as-is.
+    pub fn auto_escape(mut self, auto_escape: bool) -> Self {
+        self.auto_escape = auto_escape;
+        self
+    }
+
+    /// Cache up to `capacity` templates parsed with `Parser::parse_cached`.
+    ///
+    /// The cache is shared between clones of the `Parser`.  Evicting a template scans every cached
+    /// template, so this is meant for a bounded set of templates, not millions.
+    pub fn template_cache(mut self, capacity: usize) -> Self {
+        self.cache_capacity = Some(capacity);
+        self
+    }
+
+    /// Display dates in `tz` when rendering, like a site's time zone.
+    ///
+    /// See `Template::with_timezone`.
+    pub fn timezone(mut self, tz: TimeZone) -> Self {
+        self.timezone = Some(tz);
+        self
+    }
+
+    /// Order strings by `collation` when rendering, like a site's language.
+    ///
+    /// See `Template::with_collation`.
+    pub fn collation(mut self, collation: Collation) -> Self {
+        self.collation = Some(collation);
+        self
+    }
+
     /// Create a parser
     pub fn build(self) -> Result<Parser> {
         let Self {
@@ -157,19 +211,32 @@ where
             tags,
             filters,
             partials,
+            auto_escape,
+            cache_capacity,
+            timezone,
+            collation,
         } = self;
 
         let mut options = parser::Language::empty();
         options.blocks = blocks;
         options.tags = tags;
         options.filters = filters;
+        options.auto_escape = auto_escape;
         let options = sync::Arc::new(options);
         let partials = partials
             .map(|p| p.compile(options.clone()))
             .map(|r| r.map(Some))
             .unwrap_or(Ok(None))?
             .map(|p| p.into());
-        let p = Parser { options, partials };
+        let cache = cache_capacity.map(|c| sync::Arc::new(TemplateCache::new(c)));
+        let p = Parser {
+            options,
+            partials,
+            cache,
+            max_partials: None,
+            timezone,
+            collation,
+        };
         Ok(p)
     }
 }
@@ -184,6 +251,10 @@ where
             tags: Default::default(),
             filters: Default::default(),
             partials: Default::default(),
+            auto_escape: false,
+            cache_capacity: None,
+            timezone: None,
+            collation: None,
         }
     }
 }
@@ -218,6 +289,10 @@ where
 pub struct Parser {
     options: sync::Arc<parser::Language>,
     partials: Option<sync::Arc<dyn runtime::PartialStore + Send + Sync>>,
+    cache: Option<sync::Arc<TemplateCache>>,
+    max_partials: Option<usize>,
+    timezone: Option<TimeZone>,
+    collation: Option<Collation>,
 }
 
 impl Parser {
@@ -245,9 +320,120 @@ impl Parser {
         Ok(Template {
             template,
             partials: self.partials.clone(),
+            max_partials: self.max_partials,
+            name: None,
+            timezone: self.timezone,
+            collation: self.collation.clone(),
         })
     }
 
+    /// Parses a liquid template, reusing the result for the same `text`.
+    ///
+    /// Without `ParserBuilder::template_cache`, this always parses.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let parser = liquid::ParserBuilder::with_stdlib()
+    ///     .template_cache(100)
+    ///     .build().unwrap();
+    ///
+    /// let first = parser.parse_cached("Liquid!").unwrap();
+    /// let second = parser.parse_cached("Liquid!").unwrap();
+    /// assert!(std::sync::Arc::ptr_eq(&first, &second));
+    ///
+    /// let stats = parser.cache_stats().unwrap();
+    /// assert_eq!((stats.hits, stats.misses), (1, 1));
+    /// ```
+    pub fn parse_cached(&self, text: &str) -> Result<sync::Arc<Template>> {
+        self.parse_cached_impl(None, text)
+    }
+
+    /// Parses a liquid template, reusing the result for the same `name` and `text`.
+    ///
+    /// Lookups hash `text` instead of using it as the key, and compare the text in full on a hit.
+    /// The template is named like with `Parser::parse_named`.
+    pub fn parse_cached_named(&self, name: &str, text: &str) -> Result<sync::Arc<Template>> {
+        self.parse_cached_impl(Some(name), text)
+    }
+
+    fn parse_cached_impl(&self, name: Option<&str>, text: &str) -> Result<sync::Arc<Template>> {
+        let parse = || match name {
+            Some(name) => self.parse_named(name, text),
+            None => self.parse(text),
+        };
+        match &self.cache {
+            Some(cache) => cache.get_or_parse(name, text, parse),
+            None => parse().map(sync::Arc::new),
+        }
+    }
+
+    /// Parses a liquid template, naming it for errors and `Runtime::name`.
+    pub fn parse_named(&self, name: &str, text: &str) -> Result<Template> {
+        let template = self
+            .parse(text)
+            .context_key("template")
+            .value_with(|| name.to_owned().into())?;
+        Ok(template.with_name(KString::from_ref(name)))
+    }
+
+    /// Report what `text` depends on without rendering it.
+    ///
+    /// See `Template::analyze`.
+    ///
+    /// ```
+    /// let parser = liquid::ParserBuilder::with_stdlib().build().unwrap();
+    /// let dependencies = parser.analyze("{{ page.title | upcase }}").unwrap();
+    /// let roots: Vec<_> = dependencies.roots().collect();
+    /// assert_eq!(roots, ["page"]);
+    /// ```
+    pub fn analyze(&self, text: &str) -> Result<runtime::Dependencies> {
+        self.parse(text).map(|template| template.analyze())
+    }
+
+    /// Hits and misses for `Parser::parse_cached`, if `ParserBuilder::template_cache` was set.
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(|c| c.stats())
+    }
+
+    /// Forget all cached templates.
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+    }
+
+    /// Create a parser for templates from untrusted authors, restricted by `policy`.
+    ///
+    /// Create it once per policy and reuse it, as this copies the parser's language.  It does not
+    /// share the template cache, so trusted templates are never served to untrusted authors.
+    /// Partial-templates are parsed when the `Parser` is built, so the policy only limits how
+    /// often they can be accessed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let parser = liquid::ParserBuilder::with_stdlib().build().unwrap();
+    /// let policy = liquid::SandboxPolicy::new().deny_tags(["include"]);
+    /// let sandboxed = parser.sandboxed(&policy);
+    ///
+    /// assert!(sandboxed.parse("{{ 'Liquid!' | upcase }}").is_ok());
+    /// assert!(sandboxed.parse("{% include 'secrets' %}").is_err());
+    /// assert!(parser.parse("{% include 'secrets' %}").is_ok());
+    /// ```
+    pub fn sandboxed(&self, policy: &parser::SandboxPolicy) -> Parser {
+        let mut options = (*self.options).clone();
+        options.sandbox = Some(policy.clone());
+        Parser {
+            options: sync::Arc::new(options),
+            partials: self.partials.clone(),
+            cache: None,
+            max_partials: policy.partial_limit(),
+            timezone: self.timezone,
+            collation: self.collation.clone(),
+        }
+    }
+
     /// Parse a liquid template from a file, returning a `Result<Template, Error>`.
     /// # Examples
     ///
@@ -41,4 +41,51 @@
 //! assert_eq!(rendered, "Liquid! Number: 42");
 //! ```
 
+pub use crate::test_partials;
 pub use liquid_core::partials::*;
+pub use liquid_derive::embed_partials;
+
+/// Generates a `#[test]` that parses each partial-template of a source, like one from
+/// `embed_partials!`, so syntax errors fail `cargo test`.
+///
+/// `embed_partials!` can't parse them when building, as `liquid-derive` can't depend on the
+/// parser.  They are parsed with the stdlib, or with the `Parser` given as a third argument, like
+/// one with custom tags.  Attributes, like `#[cfg(...)]`, are passed on to the test.
+///
+/// # Example
+///
+/// ```ignore
+/// static PARTIALS: liquid::partials::EmbeddedSource =
+///     liquid::partials::embed_partials!("templates/partials");
+///
+/// liquid::partials::test_partials!(partials_parse, PARTIALS);
+/// ```
+#[macro_export]
+macro_rules! test_partials {
+    ($(#[$attr:meta])* $name:ident, $source:expr) => {
+        $crate::test_partials!(
+            $(#[$attr])* $name,
+            $source,
+            $crate::ParserBuilder::with_stdlib().build().unwrap()
+        );
+    };
+    ($(#[$attr:meta])* $name:ident, $source:expr, $parser:expr) => {
+        #[test]
+        $(#[$attr])*
+        fn $name() {
+            let source = &$source;
+            let parser = $parser;
+            let errors: Vec<_> = $crate::partials::PartialSource::names(source)
+                .into_iter()
+                .filter_map(|name| {
+                    let error = match $crate::partials::PartialSource::get(source, name) {
+                        Ok(text) => parser.parse(&text).err()?,
+                        Err(error) => error,
+                    };
+                    Some(format!("{}: {}", name, error))
+                })
+                .collect();
+            assert!(errors.is_empty(), "{}", errors.join("\n"));
+        }
+    };
+}
@@ -1,17 +1,67 @@
+use std::cell::Cell;
 use std::io::Write;
 use std::sync;
 
 use liquid_core::error::Result;
+use liquid_core::error::ResultLiquidExt;
+use liquid_core::model::Collation;
+use liquid_core::model::KString;
+use liquid_core::model::TimeZone;
 use liquid_core::runtime;
+use liquid_core::runtime::Dependencies;
 use liquid_core::runtime::PartialStore;
 use liquid_core::runtime::Renderable;
+use liquid_core::runtime::Runtime;
+use liquid_core::runtime::TaintMode;
+use liquid_core::Error;
 
 pub struct Template {
     pub(crate) template: runtime::Template,
     pub(crate) partials: Option<sync::Arc<dyn PartialStore + Send + Sync>>,
+    pub(crate) max_partials: Option<usize>,
+    pub(crate) name: Option<KString>,
+    pub(crate) timezone: Option<TimeZone>,
+    pub(crate) collation: Option<Collation>,
 }
 
 impl Template {
+    /// Name the template, like its path, for tags that depend on where it lives, like
+    /// `include_relative`.
+    pub fn with_name<S: Into<KString>>(mut self, name: S) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Display dates in `tz`, like a site's time zone, rather than the offset they were created
+    /// with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use liquid::model::TimeZone;
+    ///
+    /// let template = liquid::ParserBuilder::with_stdlib()
+    ///     .build().unwrap()
+    ///     .parse("{{ '2024-01-15 12:00:00 +0000' | date: '%H:%M %z' }}").unwrap()
+    ///     .with_timezone(TimeZone::from_name("+05:30").unwrap());
+    ///
+    /// let output = template.render(&liquid::Object::new()).unwrap();
+    /// assert_eq!(output, "17:30 +0530");
+    /// ```
+    pub fn with_timezone(mut self, tz: TimeZone) -> Self {
+        self.timezone = Some(tz);
+        self
+    }
+
+    /// Order strings by `collation`, like a site's language, rather than by code point.
+    ///
+    /// This applies to `sort`, `sort_natural`, `uniq` and string comparisons in `if`.  Requires
+    /// the `collation` feature to create a `Collation`.
+    pub fn with_collation(mut self, collation: Collation) -> Self {
+        self.collation = Some(collation);
+        self
+    }
+
     /// Renders an instance of the Template, using the given globals.
     pub fn render(&self, globals: &dyn crate::ObjectView) -> Result<String> {
         const BEST_GUESS: usize = 10_000;
@@ -23,13 +73,190 @@ impl Template {
 
     /// Renders an instance of the Template, using the given globals.
     pub fn render_to(&self, writer: &mut dyn Write, globals: &dyn crate::ObjectView) -> Result<()> {
+        self.with_runtime(globals, TaintMode::Lax, |runtime| {
+            self.template.render_to(writer, runtime)
+        })
+    }
+
+    /// The template's name, from `with_name` or `Parser::parse_named`.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(|n| n.as_str())
+    }
+
+    /// Renders an instance of the Template, checking that tainted values are escaped before
+    /// they are output.
+    ///
+    /// Mark untrusted globals with `Value::into_tainted`.  With `TaintMode::Warn`, the
+    /// problems found are returned alongside the output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use liquid::model::Value;
+    /// use liquid::TaintMode;
+    ///
+    /// let template = liquid::ParserBuilder::with_stdlib()
+    ///     .build().unwrap()
+    ///     .parse("{{ comment | escape }}").unwrap();
+    ///
+    /// let mut globals = liquid::Object::new();
+    /// globals.insert("comment".into(), Value::scalar("<b>hi</b>").into_tainted());
+    ///
+    /// let (output, warnings) = template.render_with_taint_mode(&globals, TaintMode::Error).unwrap();
+    /// assert_eq!(output, "&lt;b&gt;hi&lt;/b&gt;");
+    /// assert!(warnings.is_empty());
+    /// ```
+    pub fn render_with_taint_mode(
+        &self,
+        globals: &dyn crate::ObjectView,
+        mode: TaintMode,
+    ) -> Result<(String, Vec<Error>)> {
+        const BEST_GUESS: usize = 10_000;
+        let mut data = Vec::with_capacity(BEST_GUESS);
+        let warnings = self.render_to_with_taint_mode(&mut data, globals, mode)?;
+
+        Ok((convert_buffer(data), warnings))
+    }
+
+    /// Renders an instance of the Template, checking that tainted values are escaped before
+    /// they are output.
+    ///
+    /// See `Template::render_with_taint_mode`.
+    pub fn render_to_with_taint_mode(
+        &self,
+        writer: &mut dyn Write,
+        globals: &dyn crate::ObjectView,
+        mode: TaintMode,
+    ) -> Result<Vec<Error>> {
+        self.with_runtime(globals, mode, |runtime| {
+            self.template.render_to(writer, runtime)?;
+            let warnings = runtime
+                .registers()
+                .get_mut::<runtime::TaintRegister>()
+                .take_warnings();
+            Ok(warnings)
+        })
+    }
+
+    fn with_runtime<R>(
+        &self,
+        globals: &dyn crate::ObjectView,
+        taint_mode: TaintMode,
+        f: impl FnOnce(&dyn Runtime) -> Result<R>,
+    ) -> Result<R> {
+        let limited;
+        let partials: Option<&dyn PartialStore> = match (&self.partials, self.max_partials) {
+            (Some(partials), Some(max)) => {
+                limited = LimitedPartials::new(partials.as_ref(), max);
+                Some(&limited)
+            }
+            (Some(partials), None) => Some(partials.as_ref()),
+            (None, _) => None,
+        };
+
         let runtime = runtime::RuntimeBuilder::new().set_globals(globals);
-        let runtime = match self.partials {
-            Some(ref partials) => runtime.set_partials(partials.as_ref()),
+        let runtime = match partials {
+            Some(partials) => runtime.set_partials(partials),
+            None => runtime,
+        };
+        let runtime = match &self.name {
+            Some(name) => runtime.set_name(name.clone()),
+            None => runtime,
+        };
+        let runtime = match self.timezone {
+            Some(tz) => runtime.set_timezone(tz),
+            None => runtime,
+        };
+        let runtime = match &self.collation {
+            Some(collation) => runtime.set_collation(collation.clone()),
             None => runtime,
         };
-        let runtime = runtime.build();
-        self.template.render_to(writer, &runtime)
+        let runtime = runtime.set_taint_mode(taint_mode).build();
+        let result = f(&runtime);
+        match &self.name {
+            Some(name) => result.context_key("template").value_with(|| name.clone()),
+            None => result,
+        }
+    }
+
+    /// Finds what the Template depends on, without rendering it.
+    ///
+    /// Global variables, literal partial-template names (recursively through the configured
+    /// partials), filters and tags are reported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let template = liquid::ParserBuilder::with_stdlib()
+    ///     .build().unwrap()
+    ///     .parse("{% for p in products %}{{ p.title | upcase }}{{ shop.name }}{% endfor %}").unwrap();
+    ///
+    /// let dependencies = template.analyze();
+    /// let roots: Vec<_> = dependencies.roots().collect();
+    /// assert_eq!(roots, ["products", "shop"]);
+    /// let filters: Vec<_> = dependencies.filters().collect();
+    /// assert_eq!(filters, ["upcase"]);
+    /// ```
+    pub fn analyze(&self) -> Dependencies {
+        let analyzer = runtime::Analyzer::new();
+        let mut analyzer = match self.partials {
+            Some(ref partials) => analyzer.set_partials(partials.as_ref()),
+            None => analyzer,
+        };
+        self.template.analyze(&mut analyzer);
+        analyzer.into_dependencies()
+    }
+}
+
+/// Caps how many times partial-templates are accessed, for `SandboxPolicy::max_partials`.
+#[derive(Debug)]
+struct LimitedPartials<'p> {
+    partials: &'p dyn PartialStore,
+    remaining: Cell<usize>,
+    max: usize,
+}
+
+impl<'p> LimitedPartials<'p> {
+    fn new(partials: &'p dyn PartialStore, max: usize) -> Self {
+        Self {
+            partials,
+            remaining: Cell::new(max),
+            max,
+        }
+    }
+
+    fn consume(&self) -> Result<()> {
+        let remaining = self.remaining.get();
+        if remaining == 0 {
+            return Error::with_msg("Too many partial-templates for the sandbox policy")
+                .context("limit", self.max.to_string())
+                .into_err();
+        }
+        self.remaining.set(remaining - 1);
+        Ok(())
+    }
+}
+
+impl<'p> PartialStore for LimitedPartials<'p> {
+    fn contains(&self, name: &str) -> bool {
+        self.partials.contains(name)
+    }
+
+    fn names(&self) -> Vec<&str> {
+        self.partials.names()
+    }
+
+    /// Hides the sandbox limit error; tags use `get` to report it.
+    fn try_get(&self, name: &str) -> Option<sync::Arc<dyn Renderable>> {
+        self.get(name).ok()
+    }
+
+    fn get(&self, name: &str) -> Result<sync::Arc<dyn Renderable>> {
+        // Only count partial-templates that exist, so fallback lookups are free
+        if self.partials.contains(name) {
+            self.consume()?;
+        }
+        self.partials.get(name)
     }
 }
 
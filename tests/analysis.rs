@@ -0,0 +1,180 @@
+use liquid::partials::{EagerCompiler, InMemorySource};
+
+fn parser() -> liquid::Parser {
+    let mut partials = EagerCompiler::<InMemorySource>::empty();
+    partials.add("header", "{{ site.title }}{% include 'nav' %}");
+    partials.add(
+        "nav",
+        "{% for link in site.links %}{{ link.url }}{% endfor %}",
+    );
+    partials.add("card.liquid", "{{ product.title }}{{ shop.name }}");
+    partials.add("loop", "{{ depth }}{% include 'loop' %}");
+    liquid::ParserBuilder::with_stdlib()
+        .partials(partials)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn variable_paths() {
+    let template = parser()
+        .parse("{{ page.title }}{{ page.tags[i] }}{{ page['url'] }}")
+        .unwrap();
+    let dependencies = template.analyze();
+    let actual: Vec<_> = dependencies.variables().map(join).collect();
+    assert_eq!(actual, ["i", "page.tags", "page.title", "page.url"]);
+}
+
+#[test]
+fn locals_are_excluded() {
+    let template = parser()
+        .parse(concat!(
+            "{{ x }}",
+            "{% assign x = 1 %}{{ x }}",
+            "{% capture y %}{{ z }}{% endcapture %}{{ y }}",
+            "{% for item in items %}{{ item }}{{ forloop.index }}{% endfor %}",
+            "{{ item }}",
+        ))
+        .unwrap();
+    let dependencies = template.analyze();
+    let actual: Vec<_> = dependencies.roots().collect();
+    assert_eq!(actual, ["item", "items", "x", "z"]);
+}
+
+#[test]
+fn partials_are_followed() {
+    let template = parser()
+        .parse("{% include 'header' %}{% render 'card', product: featured %}")
+        .unwrap();
+    let dependencies = template.analyze();
+    let partials: Vec<_> = dependencies.partials().collect();
+    assert_eq!(partials, ["card.liquid", "header", "nav"]);
+    // `render` can only see what is passed in
+    let roots: Vec<_> = dependencies.roots().collect();
+    assert_eq!(roots, ["featured", "site"]);
+    let tags: Vec<_> = dependencies.tags().collect();
+    assert_eq!(tags, ["for", "include", "render"]);
+}
+
+#[test]
+fn recursive_partials() {
+    let template = parser().parse("{% include 'loop' %}").unwrap();
+    let dependencies = template.analyze();
+    let partials: Vec<_> = dependencies.partials().collect();
+    assert_eq!(partials, ["loop"]);
+    let roots: Vec<_> = dependencies.roots().collect();
+    assert_eq!(roots, ["depth"]);
+}
+
+#[test]
+fn filters() {
+    let template = parser()
+        .parse("{{ a | default: b | append: 'x' }}")
+        .unwrap();
+    let dependencies = template.analyze();
+    let filters: Vec<_> = dependencies.filters().collect();
+    assert_eq!(filters, ["append", "default"]);
+    let roots: Vec<_> = dependencies.roots().collect();
+    assert_eq!(roots, ["a", "b"]);
+}
+
+fn join(path: &[liquid::model::KString]) -> String {
+    path.iter()
+        .map(|s| s.as_str())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+#[test]
+fn parser_analyze() {
+    let dependencies: liquid::Dependencies = parser()
+        .analyze("{% include 'header' %}{{ page.title }}")
+        .unwrap();
+    let roots: Vec<_> = dependencies.roots().collect();
+    assert_eq!(roots, ["page", "site"]);
+    let partials: Vec<_> = dependencies.partials().collect();
+    assert_eq!(partials, ["header", "nav"]);
+}
+
+#[derive(Copy, Clone, Debug)]
+struct ShoutTag;
+
+impl liquid_core::TagReflection for ShoutTag {
+    fn tag(&self) -> &'static str {
+        "shout"
+    }
+
+    fn description(&self) -> &'static str {
+        ""
+    }
+}
+
+impl liquid_core::ParseTag for ShoutTag {
+    fn parse(
+        &self,
+        mut arguments: liquid_core::TagTokenIter<'_>,
+        _options: &liquid_core::Language,
+    ) -> liquid_core::Result<Box<dyn liquid_core::Renderable>> {
+        let name = arguments
+            .expect_next("Identifier expected")?
+            .as_str()
+            .to_owned();
+        Ok(Box::new(Shout(name)))
+    }
+
+    fn reflection(&self) -> &dyn liquid_core::TagReflection {
+        self
+    }
+}
+
+#[derive(Debug)]
+struct Shout(String);
+
+impl liquid_core::Renderable for Shout {
+    fn render_to(
+        &self,
+        writer: &mut dyn std::io::Write,
+        _runtime: &dyn liquid_core::Runtime,
+    ) -> liquid_core::Result<()> {
+        write!(writer, "{}!", self.0).unwrap();
+        Ok(())
+    }
+}
+
+#[test]
+fn custom_tags() {
+    let parser = liquid::ParserBuilder::with_stdlib()
+        .tag(ShoutTag)
+        .build()
+        .unwrap();
+    let dependencies = parser
+        .analyze("{% if a %}{% shout b %}{% endif %}")
+        .unwrap();
+    let tags: Vec<_> = dependencies.tags().collect();
+    assert_eq!(tags, ["if", "shout"]);
+    assert!(!dependencies.is_complete());
+
+    let dependencies = parser.analyze("{% if a %}{{ b }}{% endif %}").unwrap();
+    assert!(dependencies.is_complete());
+}
+
+#[test]
+fn filter_chains_built_by_hand() {
+    let filter = liquid_core::parser::ParseFilter::parse(
+        &liquid_lib::stdlib::Upcase,
+        liquid_core::parser::FilterArguments {
+            positional: Box::new(std::iter::empty()),
+            keyword: Box::new(std::iter::empty()),
+        },
+    )
+    .unwrap();
+    let chain = liquid_core::parser::FilterChain::new(
+        liquid_core::Expression::Variable(liquid_core::runtime::Variable::with_literal("name")),
+        vec![filter],
+    );
+    let mut analyzer = liquid_core::runtime::Analyzer::new();
+    liquid_core::Renderable::analyze(&chain, &mut analyzer);
+    let dependencies = analyzer.into_dependencies();
+    let filters: Vec<_> = dependencies.filters().collect();
+    assert_eq!(filters, ["upcase"]);
+}
@@ -0,0 +1,121 @@
+use liquid::partials::{EagerCompiler, InMemorySource};
+
+fn parser() -> liquid::Parser {
+    let mut partials = EagerCompiler::<InMemorySource>::empty();
+    partials.add("comment", "<p>{{ body }}</p>");
+    liquid::ParserBuilder::with_stdlib()
+        .auto_escape(true)
+        .partials(partials)
+        .build()
+        .unwrap()
+}
+
+fn render(text: &str) -> String {
+    let globals = liquid::object!({
+        "body": "<script>alert('hi')</script>",
+        "amp": "Tom & Jerry",
+    });
+    parser().parse(text).unwrap().render(&globals).unwrap()
+}
+
+#[test]
+fn output_is_escaped() {
+    assert_eq!(
+        render("<b>{{ body }}</b>"),
+        "<b>&lt;script&gt;alert(&#39;hi&#39;)&lt;/script&gt;</b>"
+    );
+    assert_eq!(render("{{ '<br>' }}"), "&lt;br&gt;");
+}
+
+#[test]
+fn disabled_by_default() {
+    let template = liquid::ParserBuilder::with_stdlib()
+        .build()
+        .unwrap()
+        .parse("{{ amp }}")
+        .unwrap();
+    let output = template
+        .render(&liquid::object!({ "amp": "Tom & Jerry" }))
+        .unwrap();
+    assert_eq!(output, "Tom & Jerry");
+}
+
+#[test]
+fn raw_opts_out() {
+    assert_eq!(render("{{ '<br>' | raw }}"), "<br>");
+    // Filters applied afterwards produce a new, unsafe, string
+    assert_eq!(render("{{ '<br>' | raw | upcase }}"), "&lt;BR&gt;");
+}
+
+#[test]
+fn escape_is_not_doubled() {
+    assert_eq!(render("{{ amp | escape }}"), "Tom &amp; Jerry");
+    assert_eq!(render("{{ amp | escape_once }}"), "Tom &amp; Jerry");
+}
+
+#[test]
+fn safety_survives_assign() {
+    assert_eq!(render("{% assign x = '<br>' | raw %}{{ x }}"), "<br>");
+    assert_eq!(render("{% assign x = '<br>' %}{{ x }}"), "&lt;br&gt;");
+}
+
+#[test]
+fn safety_survives_capture() {
+    assert_eq!(
+        render("{% capture x %}<i>{{ amp }}</i>{% endcapture %}{{ x }}"),
+        "<i>Tom &amp; Jerry</i>"
+    );
+}
+
+#[test]
+fn safety_survives_partials() {
+    assert_eq!(
+        render("{% include 'comment' %}"),
+        "<p>&lt;script&gt;alert(&#39;hi&#39;)&lt;/script&gt;</p>"
+    );
+    assert_eq!(
+        render("{% render 'comment', body: '<em>hi</em>' %}"),
+        "<p>&lt;em&gt;hi&lt;/em&gt;</p>"
+    );
+    assert_eq!(
+        render("{% assign b = '<em>hi</em>' | raw %}{% render 'comment', body: b %}"),
+        "<p><em>hi</em></p>"
+    );
+}
+
+#[test]
+fn tainted_safe_strings_are_escaped() {
+    let template = parser().parse("{{ body }}").unwrap();
+    let globals = liquid::object!({
+        "body": liquid::model::Value::Scalar(liquid::model::Scalar::safe("<b>hi</b>")).into_tainted(),
+    });
+    assert_eq!(template.render(&globals).unwrap(), "&lt;b&gt;hi&lt;/b&gt;");
+}
+
+#[test]
+fn cycle_is_escaped() {
+    assert_eq!(
+        render("{% cycle body, 'a' %}{% cycle body, 'a' %}"),
+        "&lt;script&gt;alert(&#39;hi&#39;)&lt;/script&gt;a"
+    );
+    assert_eq!(
+        render("{% assign x = '<br>' | raw %}{% cycle x, 'a' %}"),
+        "<br>"
+    );
+}
+
+#[test]
+fn counters_are_unchanged() {
+    assert_eq!(
+        render("{% increment c %}{% increment c %}{% decrement d %}"),
+        "01-1"
+    );
+}
+
+#[test]
+fn ifchanged_is_not_doubled() {
+    assert_eq!(
+        render("{% ifchanged %}{{ amp }}{% endifchanged %}"),
+        "Tom &amp; Jerry"
+    );
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "collation")]
+
+use liquid::model::Collation;
+
+fn render(locale: Option<&str>, text: &str) -> String {
+    let mut builder = liquid::ParserBuilder::with_stdlib();
+    if let Some(locale) = locale {
+        builder = builder.collation(Collation::new(locale).unwrap());
+    }
+    let template = builder.build().unwrap().parse(text).unwrap();
+    let globals = liquid::object!({
+        "fruits": ["Zebra", "birne", "Äpfel", "apfel"],
+    });
+    template.render(&globals).unwrap()
+}
+
+#[test]
+fn sort_by_code_point() {
+    let output = render(None, "{{ fruits | sort | join: ',' }}");
+    assert_eq!(output, "Zebra,apfel,birne,Äpfel");
+}
+
+#[test]
+fn sort_with_locale_argument() {
+    let output = render(None, "{{ fruits | sort: locale: 'de' | join: ',' }}");
+    assert_eq!(output, "apfel,Äpfel,birne,Zebra");
+}
+
+#[test]
+fn sort_natural_with_runtime_collation() {
+    let output = render(Some("de"), "{{ fruits | sort_natural | join: ',' }}");
+    assert_eq!(output, "apfel,Äpfel,birne,Zebra");
+}
+
+#[test]
+fn uniq_with_collation() {
+    // Composed and decomposed forms of "é"
+    let text = "{% assign words = 'caf\u{e9},cafe\u{301},cafe' | split: ',' %}{{ words | uniq: locale: 'fr' | size }} {{ words | uniq | size }}";
+    assert_eq!(render(None, text), "2 3");
+}
+
+#[test]
+fn if_compares_with_collation() {
+    let text = "{% if 'Äpfel' < 'Zebra' %}before{% else %}after{% endif %}";
+    assert_eq!(render(None, text), "after");
+    assert_eq!(render(Some("de"), text), "before");
+}
+
+#[test]
+fn unknown_locale_is_an_error() {
+    let template = liquid::ParserBuilder::with_stdlib()
+        .build()
+        .unwrap()
+        .parse("{{ fruits | sort: locale: 'not a locale!' }}")
+        .unwrap();
+    let globals = liquid::object!({ "fruits": ["b", "a"] });
+    template.render(&globals).unwrap_err();
+}
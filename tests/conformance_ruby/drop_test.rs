@@ -4,22 +4,57 @@ fn test_product_drop() {
     panic!("Implementation specific: drops");
 }
 
+fn tainted_product() -> liquid::Object {
+    let product = liquid::object!({
+        "user_input": "foo",
+    });
+    let mut globals = liquid::Object::new();
+    globals.insert(
+        "product".into(),
+        liquid::model::Value::Object(product).into_tainted(),
+    );
+    globals
+}
+
 #[test]
-#[should_panic]
 fn test_rendering_raises_on_tainted_attr() {
-    panic!("Implementation specific: drops");
+    let template = liquid::ParserBuilder::with_stdlib()
+        .build()
+        .unwrap()
+        .parse("{{ product.user_input }}")
+        .unwrap();
+    template
+        .render_with_taint_mode(&tainted_product(), liquid::TaintMode::Error)
+        .unwrap_err();
 }
 
 #[test]
-#[should_panic]
 fn test_rendering_warns_on_tainted_attr() {
-    panic!("Implementation specific: drops");
+    let template = liquid::ParserBuilder::with_stdlib()
+        .build()
+        .unwrap()
+        .parse("{{ product.user_input }}")
+        .unwrap();
+    let (output, warnings) = template
+        .render_with_taint_mode(&tainted_product(), liquid::TaintMode::Warn)
+        .unwrap();
+    assert_eq!(output, "foo");
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0]
+        .to_string()
+        .contains(r#"variable 'product["user_input"]' is tainted and was not escaped"#));
 }
 
 #[test]
-#[should_panic]
 fn test_rendering_doesnt_raise_on_escaped_tainted_attr() {
-    panic!("Implementation specific: drops");
+    let template = liquid::ParserBuilder::with_stdlib()
+        .build()
+        .unwrap()
+        .parse("{{ product.user_input | escape }}")
+        .unwrap();
+    template
+        .render_with_taint_mode(&tainted_product(), liquid::TaintMode::Error)
+        .unwrap();
 }
 
 #[test]
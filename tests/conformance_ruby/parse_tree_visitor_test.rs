@@ -1,187 +1,222 @@
+fn visit(template: &str) -> Vec<String> {
+    let template = liquid::ParserBuilder::with_stdlib()
+        .build()
+        .unwrap()
+        .parse(template)
+        .unwrap();
+    template.analyze().roots().map(|s| s.to_owned()).collect()
+}
+
 #[test]
-#[should_panic]
 fn test_variable() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(visit(r#"{{ test }}"#), ["test"]);
 }
 
 #[test]
-#[should_panic]
 fn test_variable_with_filter() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(
+        visit(r#"{{ test | split: infilter }}"#),
+        ["infilter", "test"]
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_dynamic_variable() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(visit(r#"{{ test[inlookup] }}"#), ["inlookup", "test"]);
 }
 
 #[test]
-#[should_panic]
 fn test_if_condition() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(visit(r#"{% if test %}{% endif %}"#), ["test"]);
 }
 
 #[test]
-#[should_panic]
 fn test_complex_if_condition() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(
+        visit(r#"{% if 1 == 1 and 2 == test %}{% endif %}"#),
+        ["test"]
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_if_body() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(visit(r#"{% if 1 == 1 %}{{ test }}{% endif %}"#), ["test"]);
 }
 
 #[test]
-#[should_panic]
 fn test_unless_condition() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(visit(r#"{% unless test %}{% endunless %}"#), ["test"]);
 }
 
 #[test]
-#[should_panic]
 fn test_complex_unless_condition() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(
+        visit(r#"{% unless 1 == 1 and 2 == test %}{% endunless %}"#),
+        ["test"]
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_unless_body() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(
+        visit(r#"{% unless 1 == 1 %}{{ test }}{% endunless %}"#),
+        ["test"]
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_elsif_condition() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(
+        visit(r#"{% if 1 == 1 %}{% elsif test %}{% endif %}"#),
+        ["test"]
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_complex_elsif_condition() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(
+        visit(r#"{% if 1 == 1 %}{% elsif 1 == 1 and 2 == test %}{% endif %}"#),
+        ["test"]
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_elsif_body() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(
+        visit(r#"{% if 1 == 1 %}{% elsif 2 == 2 %}{{ test }}{% endif %}"#),
+        ["test"]
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_else_body() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(
+        visit(r#"{% if 1 == 1 %}{% else %}{{ test }}{% endif %}"#),
+        ["test"]
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_case_left() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(visit(r#"{% case test %}{% endcase %}"#), ["test"]);
 }
 
 #[test]
-#[should_panic]
 fn test_case_condition() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(
+        visit(r#"{% case 1 %}{% when test %}{% endcase %}"#),
+        ["test"]
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_case_when_body() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(
+        visit(r#"{% case 1 %}{% when 2 %}{{ test }}{% endcase %}"#),
+        ["test"]
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_case_else_body() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(
+        visit(r#"{% case 1 %}{% else %}{{ test }}{% endcase %}"#),
+        ["test"]
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_for_in() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(visit(r#"{% for x in test %}{% endfor %}"#), ["test"]);
 }
 
 #[test]
-#[should_panic]
 fn test_for_limit() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(
+        visit(r#"{% for x in (1..5) limit: test %}{% endfor %}"#),
+        ["test"]
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_for_offset() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(
+        visit(r#"{% for x in (1..5) offset: test %}{% endfor %}"#),
+        ["test"]
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_for_body() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(
+        visit(r#"{% for x in (1..5) %}{{ test }}{% endfor %}"#),
+        ["test"]
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_tablerow_in() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(
+        visit(r#"{% tablerow x in test %}{% endtablerow %}"#),
+        ["test"]
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_tablerow_limit() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(
+        visit(r#"{% tablerow x in (1..5) limit: test %}{% endtablerow %}"#),
+        ["test"]
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_tablerow_offset() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(
+        visit(r#"{% tablerow x in (1..5) offset: test %}{% endtablerow %}"#),
+        ["test"]
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_tablerow_body() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(
+        visit(r#"{% tablerow x in (1..5) %}{{ test }}{% endtablerow %}"#),
+        ["test"]
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_cycle() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(visit(r#"{% cycle test %}"#), ["test"]);
 }
 
 #[test]
-#[should_panic]
 fn test_assign() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(visit(r#"{% assign x = test %}"#), ["test"]);
 }
 
 #[test]
-#[should_panic]
 fn test_capture() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(
+        visit(r#"{% capture x %}{{ test }}{% endcapture %}"#),
+        ["test"]
+    );
 }
 
 #[test]
-#[should_panic]
 fn test_include() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(visit(r#"{% include test %}"#), ["test"]);
 }
 
 #[test]
-#[should_panic]
 fn test_include_with() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(visit(r#"{% include "hi" with test %}"#), ["test"]);
 }
 
 #[test]
-#[should_panic]
 fn test_include_for() {
-    panic!("Implementation specific: parsing API");
+    assert_eq!(visit(r#"{% include "hi" for test %}"#), ["test"]);
 }
 
 #[test]
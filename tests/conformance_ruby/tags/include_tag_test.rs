@@ -49,7 +49,6 @@ fn test_include_tag_looks_for_file_system_in_registers_first() {
 }
 
 #[test]
-#[should_panic] // liquid-rust#237
 fn test_include_tag_with() {
     assert_template_result!(
         "Product: Draft 151cm ",
@@ -70,7 +69,6 @@ fn test_include_tag_with_default_name() {
 }
 
 #[test]
-#[should_panic] // liquid-rust#237
 fn test_include_tag_for() {
     assert_template_result!(
         "Product: Draft 151cm Product: Element 155cm ",
@@ -134,7 +132,6 @@ fn test_nested_include_tag() {
 }
 
 #[test]
-#[should_panic] // liquid-rust#237
 fn test_nested_include_with_variable() {
     assert_template_result!(
         "Product: Draft 151cm details ",
@@ -182,7 +179,6 @@ fn test_recursively_included_template_does_not_produce_endless_loop() {
 }
 
 #[test]
-#[should_panic] // liquid-rust#275
 fn test_dynamically_chosen_template() {
     assert_template_result!(
         "Test123",
@@ -266,7 +262,6 @@ fn test_render_raise_argument_error_when_template_is_undefined() {
 }
 
 #[test]
-#[should_panic] // liquid-rust#275
 fn test_including_via_variable_value() {
     assert_template_result!(
         "from TestFileSystem",
@@ -118,3 +118,129 @@ fn test_borrow_object() {
     assert_eq!(uut.contains_key("s"), true);
     assert!(uut.get("s").is_some());
 }
+
+#[derive(ObjectView, ValueView, Debug)]
+struct TestExtra {
+    bio: &'static str,
+}
+
+#[derive(ObjectView, ValueView, Debug)]
+#[liquid(getter = "full_name")]
+struct TestAttributes {
+    #[liquid(rename = "first")]
+    first_name: &'static str,
+    last_name: &'static str,
+    #[liquid(skip)]
+    #[allow(dead_code)]
+    password: &'static str,
+    #[liquid(skip_if = "Option::is_none")]
+    email: Option<&'static str>,
+    #[liquid(flatten)]
+    extra: TestExtra,
+    #[liquid(skip)]
+    full_name: std::cell::OnceCell<String>,
+}
+
+impl TestAttributes {
+    fn new(email: Option<&'static str>) -> Self {
+        Self {
+            first_name: "Ada",
+            last_name: "Lovelace",
+            password: "secret",
+            email,
+            extra: TestExtra { bio: "Analyst" },
+            full_name: Default::default(),
+        }
+    }
+
+    fn full_name(&self) -> &String {
+        self.full_name
+            .get_or_init(|| format!("{} {}", self.first_name, self.last_name))
+    }
+}
+
+#[test]
+fn test_attributes_object() {
+    let uut = TestAttributes::new(None);
+
+    let keys: Vec<_> = uut.keys().map(|k| k.into_owned()).collect();
+    assert_eq!(keys, ["first", "last_name", "bio", "full_name"]);
+    assert_eq!(uut.size(), 4i64);
+    assert_eq!(uut.get("first").unwrap().to_kstr(), "Ada");
+    assert!(uut.get("first_name").is_none());
+    assert!(uut.get("password").is_none());
+    assert_eq!(uut.contains_key("email"), false);
+    assert_eq!(uut.get("bio").unwrap().to_kstr(), "Analyst");
+    assert_eq!(uut.get("full_name").unwrap().to_kstr(), "Ada Lovelace");
+
+    let uut = TestAttributes::new(Some("ada@example.com"));
+    assert_eq!(uut.size(), 5i64);
+    assert_eq!(uut.get("email").unwrap().to_kstr(), "ada@example.com");
+    assert_eq!(
+        uut.to_value(),
+        liquid::model::value!({
+            "first": "Ada",
+            "last_name": "Lovelace",
+            "email": "ada@example.com",
+            "bio": "Analyst",
+            "full_name": "Ada Lovelace",
+        })
+    );
+}
+
+#[derive(ObjectView, ValueView, Debug)]
+enum TestEnum {
+    Circle {
+        radius: i64,
+    },
+    #[liquid(rename = "square")]
+    Square {
+        #[liquid(rename = "length")]
+        side: i64,
+    },
+    Point,
+}
+
+#[derive(ObjectView, ValueView, Debug)]
+#[liquid(tag = "kind")]
+enum TestTagged {
+    Empty,
+}
+
+#[test]
+fn test_enum_object() {
+    let uut = TestEnum::Circle { radius: 2 };
+    assert_eq!(uut.size(), 2i64);
+    assert_eq!(uut.get("type").unwrap().to_kstr(), "Circle");
+    assert_eq!(uut.get("radius").unwrap().as_scalar().unwrap(), 2i64);
+    assert!(uut.get("length").is_none());
+
+    let uut = TestEnum::Square { side: 3 };
+    assert_eq!(
+        uut.to_value(),
+        liquid::model::value!({"type": "square", "length": 3})
+    );
+
+    let uut = TestEnum::Point;
+    assert_eq!(uut.to_value(), liquid::model::value!({"type": "Point"}));
+
+    let uut = TestTagged::Empty;
+    assert_eq!(uut.to_value(), liquid::model::value!({"kind": "Empty"}));
+}
+
+#[test]
+fn test_enum_render() {
+    let template = liquid::ParserBuilder::with_stdlib()
+        .build()
+        .unwrap()
+        .parse("{% if shape.type == \"Circle\" %}r={{ shape.radius }}{% endif %}")
+        .unwrap();
+    #[derive(ObjectView, ValueView, Debug)]
+    struct Globals {
+        shape: TestEnum,
+    }
+    let globals = Globals {
+        shape: TestEnum::Circle { radius: 2 },
+    };
+    assert_eq!(template.render(&globals).unwrap(), "r=2");
+}
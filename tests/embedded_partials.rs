@@ -0,0 +1,43 @@
+use liquid::partials::{embed_partials, EagerCompiler, EmbeddedSource, PartialSource};
+
+static THEME: EmbeddedSource = embed_partials!("tests/fixtures/partials/theme");
+
+#[test]
+fn names() {
+    assert_eq!(THEME.names(), ["header.liquid", "snippets/_card.liquid"]);
+    assert_eq!(THEME.try_get("header.liquid").unwrap(), "theme header");
+    assert!(!THEME.contains("header"));
+}
+
+#[test]
+fn render() {
+    let parser = liquid::ParserBuilder::with_stdlib()
+        .partials(EagerCompiler::new(THEME))
+        .build()
+        .unwrap();
+    let template = parser
+        .parse("{% include 'header.liquid' %}|{% render 'snippets/_card.liquid', title: 'A' %}")
+        .unwrap();
+    let output = template.render(&liquid::Object::new()).unwrap();
+    assert_eq!(output, "theme header|card A");
+}
+
+#[test]
+fn crate_path() {
+    let source: liquid_core::partials::EmbeddedSource =
+        embed_partials!("tests/fixtures/partials/theme", crate = ::liquid_core);
+    assert_eq!(source.names(), THEME.names());
+}
+
+liquid::partials::test_partials!(theme_parses, THEME);
+
+liquid::partials::test_partials!(
+    #[should_panic(expected = "broken.liquid")]
+    broken_partials_fail,
+    {
+        let mut source = liquid::partials::InMemorySource::new();
+        source.add("fine.liquid", "{{ x }}");
+        source.add("broken.liquid", "{% if x %}");
+        source
+    }
+);
@@ -0,0 +1,47 @@
+use liquid::partials::InMemorySource;
+
+fn env() -> liquid::Environment {
+    let mut source = InMemorySource::new();
+    source.add(
+        "pages/index",
+        "{% include 'header' %}|{% render 'card', title: 'A' %}",
+    );
+    source.add("pages/broken", "{{ 1 | missing_filter }}");
+    source.add("pages/failing", "{{ nope }}");
+    source.add("header", "header");
+    source.add("card", "card {{ title }}");
+    liquid::Environment::new(
+        liquid::ParserBuilder::with_stdlib().template_cache(10),
+        source,
+    )
+    .unwrap()
+}
+
+#[test]
+fn get_template() {
+    let env = env();
+    let template = env.get_template("pages/index").unwrap();
+    assert_eq!(template.name(), Some("pages/index"));
+    let output = template.render(&liquid::Object::new()).unwrap();
+    assert_eq!(output, "header|card A");
+
+    let again = env.get_template("pages/index").unwrap();
+    assert!(std::sync::Arc::ptr_eq(&template, &again));
+}
+
+#[test]
+fn missing_template() {
+    let env = env();
+    assert!(env.get_template("pages/missing").is_err());
+}
+
+#[test]
+fn errors_are_named() {
+    let env = env();
+    let error = env.get_template("pages/broken").err().unwrap();
+    assert!(error.to_string().contains("pages/broken"), "{}", error);
+
+    let template = env.get_template("pages/failing").unwrap();
+    let error = template.render(&liquid::Object::new()).unwrap_err();
+    assert!(error.to_string().contains("pages/failing"), "{}", error);
+}
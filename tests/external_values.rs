@@ -0,0 +1,43 @@
+#![cfg(any(feature = "serde_json", feature = "serde_yaml", feature = "toml"))]
+
+const TEMPLATE: &str =
+    "{{ site.name }}: {% for post in posts %}{{ post.title | upcase }}{% unless forloop.last %}, {% endunless %}{% endfor %}";
+
+fn render(globals: &dyn liquid::ObjectView) -> String {
+    liquid::ParserBuilder::with_stdlib()
+        .build()
+        .unwrap()
+        .parse(TEMPLATE)
+        .unwrap()
+        .render(globals)
+        .unwrap()
+}
+
+#[test]
+#[cfg(feature = "serde_json")]
+fn renders_json() {
+    let globals: serde_json::Map<String, serde_json::Value> = serde_json::from_str(
+        r#"{"site": {"name": "Blog"}, "posts": [{"title": "one"}, {"title": "two"}]}"#,
+    )
+    .unwrap();
+    assert_eq!(render(&globals), "Blog: ONE, TWO");
+}
+
+#[test]
+#[cfg(feature = "serde_yaml")]
+fn renders_yaml() {
+    let globals: serde_yaml::Mapping =
+        serde_yaml::from_str("site:\n  name: Blog\nposts:\n  - title: one\n  - title: two\n")
+            .unwrap();
+    assert_eq!(render(&globals), "Blog: ONE, TWO");
+}
+
+#[test]
+#[cfg(feature = "toml")]
+fn renders_toml() {
+    let globals: toml::Table = toml::from_str(
+        "[site]\nname = \"Blog\"\n\n[[posts]]\ntitle = \"one\"\n\n[[posts]]\ntitle = \"two\"\n",
+    )
+    .unwrap();
+    assert_eq!(render(&globals), "Blog: ONE, TWO");
+}
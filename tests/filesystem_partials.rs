@@ -0,0 +1,81 @@
+use liquid::partials::{FileSystemSource, LazyCompiler, PartialSource};
+
+fn source() -> FileSystemSource {
+    FileSystemSource::new([
+        "tests/fixtures/partials/theme",
+        "tests/fixtures/partials/base",
+    ])
+}
+
+#[test]
+fn earlier_roots_win() {
+    let source = source();
+    assert_eq!(source.try_get("header").unwrap(), "theme header");
+    assert_eq!(source.try_get("footer").unwrap(), "base footer");
+}
+
+#[test]
+fn extension_inference() {
+    let source = source();
+    assert_eq!(source.try_get("footer.liquid").unwrap(), "base footer");
+    assert_eq!(source.try_get("snippets/card").unwrap(), "card {{ title }}");
+    assert_eq!(
+        source.try_get("snippets/_card.liquid").unwrap(),
+        "card {{ title }}"
+    );
+    assert!(!source.contains("raw"));
+    assert_eq!(source.try_get("raw.html").unwrap(), "<p>raw</p>");
+
+    let source = source.with_extensions(["html"]);
+    assert_eq!(source.try_get("raw").unwrap(), "<p>raw</p>");
+    assert!(!source.contains("snippets/card"));
+}
+
+#[test]
+fn names() {
+    let source = source();
+    assert_eq!(source.names(), ["footer", "header", "snippets/card"]);
+    for name in source.names() {
+        assert!(source.contains(name));
+    }
+
+    let source = source.with_extensions(["html"]);
+    assert_eq!(source.names(), ["raw"]);
+}
+
+#[test]
+fn eager_compiler_finds_names() {
+    let parser = liquid::ParserBuilder::with_stdlib()
+        .partials(liquid::partials::EagerCompiler::new(source()))
+        .build()
+        .unwrap();
+    let template = parser
+        .parse("{% include 'footer' %}|{% render 'snippets/card', title: 'x' %}")
+        .unwrap();
+    let output = template.render(&liquid::Object::new()).unwrap();
+    assert_eq!(output, "base footer|card x");
+}
+
+#[test]
+fn traversal_is_refused() {
+    let source = source();
+    assert!(!source.contains("../secret"));
+    assert!(!source.contains("snippets/../../secret.liquid"));
+    assert!(!source.contains("../base/footer"));
+    let absolute = std::fs::canonicalize("tests/fixtures/partials/base/footer.liquid").unwrap();
+    assert!(!source.contains(absolute.to_str().unwrap()));
+    assert!(source.try_get("").is_none());
+}
+
+#[test]
+fn render() {
+    let parser = liquid::ParserBuilder::with_stdlib()
+        .partials(LazyCompiler::new(source()))
+        .build()
+        .unwrap();
+    let template = parser
+        .parse("{% include 'header' %}|{% render 'snippets/card', title: 'x' %}")
+        .unwrap();
+    let output = template.render(&liquid::Object::new()).unwrap();
+    assert_eq!(output, "theme header|card x");
+}
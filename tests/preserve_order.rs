@@ -0,0 +1,17 @@
+#![cfg(feature = "preserve_order")]
+
+#[test]
+fn renders_in_insertion_order() {
+    let globals: liquid::Object =
+        serde_json::from_str(r#"{"page": {"title": "Home", "author": "Ada", "date": "today"}}"#)
+            .unwrap();
+    let template = liquid::ParserBuilder::with_stdlib()
+        .build()
+        .unwrap()
+        .parse("{% for pair in page %}{{ pair[0] }}={{ pair[1] }};{% endfor %}")
+        .unwrap();
+    assert_eq!(
+        template.render(&globals).unwrap(),
+        "title=Home;author=Ada;date=today;"
+    );
+}
@@ -0,0 +1,134 @@
+use liquid::partials::{EagerCompiler, InMemorySource};
+use liquid::SandboxPolicy;
+
+fn parser() -> liquid::Parser {
+    let mut partials = EagerCompiler::<InMemorySource>::empty();
+    partials.add("item", "[{{ i }}]");
+    liquid::ParserBuilder::with_stdlib()
+        .partials(partials)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn trusted_parse_is_unrestricted() {
+    let parser = parser();
+    let policy = SandboxPolicy::new().deny_tags(["include"]);
+    parser
+        .sandboxed(&policy)
+        .parse("{% include 'item' %}")
+        .err()
+        .unwrap();
+    parser.parse("{% include 'item' %}").unwrap();
+}
+
+#[test]
+fn denied_plugins() {
+    let parser = parser();
+    let policy = SandboxPolicy::new()
+        .deny_tags(["assign"])
+        .deny_blocks(["capture"])
+        .deny_filters(["upcase"]);
+    let error = parser
+        .sandboxed(&policy)
+        .parse("{% assign x = 1 %}")
+        .err()
+        .unwrap();
+    assert!(error
+        .to_string()
+        .contains("Tag is not allowed by the sandbox policy"));
+    parser
+        .sandboxed(&policy)
+        .parse("{% capture x %}{% endcapture %}")
+        .err()
+        .unwrap();
+    let error = parser
+        .sandboxed(&policy)
+        .parse("{% if true %}{{ 'a' | upcase }}{% endif %}")
+        .err()
+        .unwrap();
+    assert!(error
+        .to_string()
+        .contains("Filter is not allowed by the sandbox policy"));
+    parser
+        .sandboxed(&policy)
+        .parse("{{ 'a' | downcase }}")
+        .unwrap();
+}
+
+#[test]
+fn allowed_plugins() {
+    let parser = parser();
+    let policy = SandboxPolicy::new()
+        .allow_tags(Vec::<String>::new())
+        .allow_blocks(["if"])
+        .allow_filters(["upcase"]);
+    parser
+        .sandboxed(&policy)
+        .parse("{% if true %}{{ 'a' | upcase }}{% endif %}")
+        .unwrap();
+    parser
+        .sandboxed(&policy)
+        .parse("{% for i in (1..2) %}{% endfor %}")
+        .err()
+        .unwrap();
+    parser
+        .sandboxed(&policy)
+        .parse("{{ 'a' | downcase }}")
+        .err()
+        .unwrap();
+    parser
+        .sandboxed(&policy)
+        .parse("{% assign x = 1 %}")
+        .err()
+        .unwrap();
+}
+
+#[test]
+fn source_len() {
+    let parser = parser();
+    let policy = SandboxPolicy::new().max_source_len(8);
+    parser.sandboxed(&policy).parse("12345678").unwrap();
+    let error = parser.sandboxed(&policy).parse("123456789").err().unwrap();
+    assert!(error
+        .to_string()
+        .contains("Template exceeds the sandbox policy"));
+}
+
+#[test]
+fn partial_limit() {
+    let parser = parser();
+    let policy = SandboxPolicy::new().max_partials(3);
+    let globals = liquid::Object::new();
+
+    let template = parser
+        .sandboxed(&policy)
+        .parse("{% for i in (1..3) %}{% include 'item' %}{% endfor %}")
+        .unwrap();
+    assert_eq!(template.render(&globals).unwrap(), "[1][2][3]");
+    // The limit applies to each render separately
+    assert_eq!(template.render(&globals).unwrap(), "[1][2][3]");
+
+    let template = parser
+        .sandboxed(&policy)
+        .parse("{% for i in (1..4) %}{% include 'item' %}{% endfor %}")
+        .unwrap();
+    let error = template.render(&globals).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("Too many partial-templates for the sandbox policy"));
+}
+
+#[test]
+fn partial_limit_error_is_reported_for_render() {
+    let parser = parser();
+    let policy = SandboxPolicy::new().max_partials(1);
+    let template = parser
+        .sandboxed(&policy)
+        .parse("{% render 'item', i: 1 %}{% render 'item', i: 2 %}")
+        .unwrap();
+    let error = template.render(&liquid::Object::new()).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("Too many partial-templates for the sandbox policy"));
+}
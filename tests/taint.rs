@@ -0,0 +1,117 @@
+use liquid::model::Value;
+use liquid::TaintMode;
+
+fn render(text: &str) -> Result<(String, Vec<liquid::Error>), liquid::Error> {
+    let mut globals = liquid::Object::new();
+    globals.insert("comment".into(), Value::scalar("<script>").into_tainted());
+    globals.insert("name".into(), Value::scalar("Alice"));
+    liquid::ParserBuilder::with_stdlib()
+        .build()
+        .unwrap()
+        .parse(text)
+        .unwrap()
+        .render_with_taint_mode(&globals, TaintMode::Error)
+}
+
+#[test]
+fn untainted_values_pass() {
+    let (output, _) = render("{{ name }}").unwrap();
+    assert_eq!(output, "Alice");
+}
+
+#[test]
+fn taint_survives_filters() {
+    render("{{ comment | upcase }}").unwrap_err();
+    render("{{ name | append: comment }}").unwrap_err();
+    let (output, _) = render("{{ comment | upcase | escape_once }}").unwrap();
+    assert_eq!(output, "&lt;SCRIPT&gt;");
+}
+
+#[test]
+fn taint_survives_assign() {
+    render("{% assign x = comment | upcase %}{{ x }}").unwrap_err();
+    let (output, _) = render("{% assign x = comment | escape %}{{ x }}").unwrap();
+    assert_eq!(output, "&lt;script&gt;");
+}
+
+#[test]
+fn lax_by_default() {
+    let mut globals = liquid::Object::new();
+    globals.insert("comment".into(), Value::scalar("<script>").into_tainted());
+    let output = liquid::ParserBuilder::with_stdlib()
+        .build()
+        .unwrap()
+        .parse("{{ comment }}")
+        .unwrap()
+        .render(&globals)
+        .unwrap();
+    assert_eq!(output, "<script>");
+}
+
+#[test]
+fn auto_escape_counts_as_escaping() {
+    let mut globals = liquid::Object::new();
+    globals.insert("comment".into(), Value::scalar("<script>").into_tainted());
+    let (output, warnings) = liquid::ParserBuilder::with_stdlib()
+        .auto_escape(true)
+        .build()
+        .unwrap()
+        .parse("{{ comment }}")
+        .unwrap()
+        .render_with_taint_mode(&globals, TaintMode::Error)
+        .unwrap();
+    assert_eq!(output, "&lt;script&gt;");
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn render_to_reports_warnings() {
+    let mut globals = liquid::Object::new();
+    globals.insert("comment".into(), Value::scalar("<script>").into_tainted());
+    let template = liquid::ParserBuilder::with_stdlib()
+        .build()
+        .unwrap()
+        .parse("{{ comment }}|{{ 'x' | append: comment }}")
+        .unwrap();
+    let mut output = Vec::new();
+    let warnings = template
+        .render_to_with_taint_mode(&mut output, &globals, TaintMode::Warn)
+        .unwrap();
+    assert_eq!(output, b"<script>|x<script>");
+    assert_eq!(warnings.len(), 2);
+}
+
+#[test]
+fn taint_is_cleared_by_non_strings() {
+    let (output, _) = render("{{ comment | size }}|{{ comment | size | append: 'x' }}").unwrap();
+    assert_eq!(output, "8|8x");
+    render("{{ comment | split: 'x' }}").unwrap_err();
+}
+
+#[test]
+fn errors_name_the_tainted_variable() {
+    let error = render("{{ 'a' | append: comment }}").unwrap_err();
+    assert!(
+        error.to_string().contains("variable 'comment'"),
+        "{}",
+        error
+    );
+    let error = render("{{ comment | append: name }}").unwrap_err();
+    assert!(
+        error.to_string().contains("variable 'comment'"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn cycle_reports_taint() {
+    let error = render("{% cycle comment, name %}").unwrap_err();
+    assert!(
+        error.to_string().contains("variable 'comment'"),
+        "{}",
+        error
+    );
+    let (output, _) = render("{% cycle name, comment %}").unwrap();
+    assert_eq!(output, "Alice");
+}
@@ -0,0 +1,76 @@
+use std::sync::Arc;
+
+fn parser(capacity: usize) -> liquid::Parser {
+    liquid::ParserBuilder::with_stdlib()
+        .template_cache(capacity)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn reuses_templates() {
+    let parser = parser(10);
+    let first = parser.parse_cached("{{ a }}").unwrap();
+    let second = parser.parse_cached("{{ a }}").unwrap();
+    let other = parser.parse_cached("{{ b }}").unwrap();
+    assert!(Arc::ptr_eq(&first, &second));
+    assert!(!Arc::ptr_eq(&first, &other));
+
+    let stats = parser.cache_stats().unwrap();
+    assert_eq!((stats.hits, stats.misses, stats.len), (1, 2, 2));
+}
+
+#[test]
+fn evicts_least_recently_used() {
+    let parser = parser(2);
+    let a = parser.parse_cached("a").unwrap();
+    parser.parse_cached("b").unwrap();
+    parser.parse_cached("a").unwrap();
+    parser.parse_cached("c").unwrap();
+
+    assert!(Arc::ptr_eq(&a, &parser.parse_cached("a").unwrap()));
+    let stats = parser.cache_stats().unwrap();
+    assert_eq!((stats.hits, stats.misses, stats.len), (2, 3, 2));
+
+    // "b" was evicted
+    parser.parse_cached("b").unwrap();
+    assert_eq!(parser.cache_stats().unwrap().misses, 4);
+}
+
+#[test]
+fn named_templates_reparse_on_change() {
+    let parser = parser(10);
+    let first = parser.parse_cached_named("page", "one").unwrap();
+    let second = parser.parse_cached_named("page", "one").unwrap();
+    let changed = parser.parse_cached_named("page", "two").unwrap();
+    assert!(Arc::ptr_eq(&first, &second));
+    assert_eq!(changed.render(&liquid::Object::new()).unwrap(), "two");
+}
+
+#[test]
+fn errors_are_not_cached() {
+    let parser = parser(10);
+    assert!(parser.parse_cached("{% if %}").is_err());
+    assert_eq!(parser.cache_stats().unwrap().len, 0);
+}
+
+#[test]
+fn shared_between_clones_and_threads() {
+    let parser = parser(10);
+    let first = parser.parse_cached("{{ a }}").unwrap();
+    let clone = parser.clone();
+    let second = std::thread::spawn(move || clone.parse_cached("{{ a }}").unwrap())
+        .join()
+        .unwrap();
+    assert!(Arc::ptr_eq(&first, &second));
+
+    parser.clear_cache();
+    assert_eq!(parser.cache_stats().unwrap().len, 0);
+}
+
+#[test]
+fn uncached() {
+    let parser = liquid::ParserBuilder::with_stdlib().build().unwrap();
+    assert!(parser.parse_cached("{{ a }}").is_ok());
+    assert!(parser.cache_stats().is_none());
+}
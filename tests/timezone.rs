@@ -0,0 +1,39 @@
+use liquid::model::TimeZone;
+
+fn render(tz: &str, text: &str) -> String {
+    let template = liquid::ParserBuilder::with_stdlib()
+        .timezone(TimeZone::from_name(tz).unwrap())
+        .build()
+        .unwrap()
+        .parse(text)
+        .unwrap();
+    template.render(&liquid::Object::new()).unwrap()
+}
+
+#[test]
+fn date_uses_default_timezone() {
+    let output = render(
+        "-08:00",
+        "{{ '2024-01-15 12:00:00 +0000' | date: '%Y-%m-%d %H:%M %z' }}",
+    );
+    assert_eq!(output, "2024-01-15 04:00 -0800");
+}
+
+#[test]
+fn date_without_default_timezone() {
+    let template = liquid::ParserBuilder::with_stdlib()
+        .build()
+        .unwrap()
+        .parse("{{ '2024-01-15 12:00:00 +0100' | date: '%H:%M %z' }}")
+        .unwrap();
+    let output = template.render(&liquid::Object::new()).unwrap();
+    assert_eq!(output, "12:00 +0100");
+}
+
+#[test]
+#[cfg(feature = "tz")]
+fn date_follows_daylight_saving() {
+    let text = "{{ '2024-03-10 06:59:00 +0000' | date: '%H:%M %z' }} {{ '2024-03-10 07:00:00 +0000' | date: '%H:%M %z' }}";
+    let output = render("America/New_York", text);
+    assert_eq!(output, "01:59 -0500 03:00 -0400");
+}